@@ -0,0 +1,92 @@
+use nae::prelude::*;
+
+#[nae::main]
+fn main() {
+    nae::init_with(|app| State::new(app))
+        .draw(draw)
+        .update(update)
+        .build()
+        .unwrap();
+}
+
+fn bindings() -> ActionMap {
+    let mut map = ActionMap::new();
+    map.bind("jump", KeyCode::Space)
+        .bind("jump", GamepadButton::South)
+        .bind("swap", KeyCode::Tab)
+        .bind_axis(
+            "move_x",
+            AxisBinding::Keys {
+                negative: KeyCode::A,
+                positive: KeyCode::D,
+            },
+        )
+        .bind_axis("move_x", GamepadAxis::LeftX);
+    map
+}
+
+fn update(app: &mut App, state: &mut State) {
+    // Rebind jump to W or Space on each press of Tab
+    if app.actions.was_pressed("swap") {
+        state.alternative = !state.alternative;
+        let key = if state.alternative {
+            KeyCode::W
+        } else {
+            KeyCode::Space
+        };
+        app.actions
+            .map
+            .rebind("jump", key)
+            .bind("jump", GamepadButton::South);
+    }
+
+    state.x += app.actions.axis("move_x") * 300.0 * app.delta;
+
+    if app.actions.was_pressed("jump") && state.y >= 300.0 {
+        state.speed = -600.0;
+    }
+
+    state.speed += 1500.0 * app.delta;
+    state.y = (state.y + state.speed * app.delta).min(300.0);
+}
+
+fn draw(app: &mut App, state: &mut State) {
+    let draw = app.draw();
+    draw.begin(Color::new(0.1, 0.2, 0.3, 1.0));
+
+    draw.color = Color::RED;
+    draw.circle(state.x, state.y, 50.0);
+
+    draw.color = Color::WHITE;
+    let jump = if state.alternative { "W" } else { "SPACE" };
+    draw.text(
+        &state.font,
+        &format!("Use A and D to move, {} to jump and TAB to rebind.", jump),
+        10.0,
+        10.0,
+        20.0,
+    );
+
+    draw.end();
+}
+
+struct State {
+    x: f32,
+    y: f32,
+    speed: f32,
+    alternative: bool,
+    font: Font,
+}
+
+impl State {
+    fn new(app: &mut App) -> Self {
+        app.actions.map = bindings();
+        State {
+            font: Font::from_bytes(app, include_bytes!("assets/Ubuntu-B.ttf")).unwrap(),
+            x: 400.0,
+            y: 300.0,
+            speed: 0.0,
+            alternative: false,
+        }
+    }
+}
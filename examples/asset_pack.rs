@@ -0,0 +1,36 @@
+use nae::prelude::*;
+
+// Create the pack first with `cargo run -p nae-pack -- ./examples/assets ./examples/assets.pack`
+#[nae::main]
+fn main() {
+    nae::init_with(init).draw(draw).build().unwrap();
+}
+
+fn init(app: &mut App) -> State {
+    // The pack is downloaded with a single request and the files are read from it
+    app.vfs
+        .mount_pack_file("assets", "./examples/assets.pack", 0);
+
+    State {
+        font: app.load_resource("assets/Ubuntu-B.ttf").unwrap(),
+        bunny: app.load_resource("assets/bunny.png").unwrap(),
+        ferris: app.load_resource("assets/ferris.png").unwrap(),
+    }
+}
+
+fn draw(app: &mut App, state: &mut State) {
+    let draw = app.draw();
+    draw.begin(Color::new(0.1, 0.2, 0.3, 1.0));
+
+    draw.image(&state.bunny, 100.0, 200.0);
+    draw.image(&state.ferris, 300.0, 200.0);
+    draw.text(&state.font, "Loaded from assets.pack", 10.0, 10.0, 20.0);
+
+    draw.end();
+}
+
+struct State {
+    font: Font,
+    bunny: Texture,
+    ferris: Texture,
+}
@@ -0,0 +1,66 @@
+use nae::prelude::*;
+
+#[nae::main]
+fn main() {
+    nae::init_with(|app| State {
+        font: Font::from_bytes(app, include_bytes!("assets/Ubuntu-B.ttf")).unwrap(),
+        msg: "".to_string(),
+    })
+    .draw(draw)
+    .event(event)
+    .build()
+    .unwrap();
+}
+
+fn event(app: &mut App, state: &mut State, evt: Event) {
+    match evt {
+        Event::KeyDown { key, modifiers, .. } if modifiers.ctrl() || modifiers.logo() => {
+            match key {
+                KeyCode::C => {
+                    if let Err(e) = app.set_clipboard_text(&state.msg) {
+                        println!("Error copying the text: {}", e);
+                    }
+                }
+                KeyCode::V => {
+                    if let Some(text) = app.clipboard_text() {
+                        state.msg.push_str(&text);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Event::KeyDown {
+            key: KeyCode::Back, ..
+        } => {
+            let _ = state.msg.pop();
+        }
+        Event::ReceivedCharacter(c) if !c.is_control() && !app.keyboard.modifiers().ctrl() => {
+            state.msg.push(c);
+        }
+        _ => {}
+    }
+}
+
+fn draw(app: &mut App, state: &mut State) {
+    let draw = app.draw();
+    draw.begin(Color::new(0.1, 0.2, 0.3, 1.0));
+
+    draw.color = Color::YELLOW;
+    draw.text(
+        &state.font,
+        "Write something, use Ctrl+C to copy it and Ctrl+V to paste:",
+        10.0,
+        10.0,
+        20.0,
+    );
+
+    draw.color = Color::WHITE;
+    draw.text_ext(&state.font, &state.msg, 10.0, 50.0, 20.0, 780.0);
+
+    draw.end();
+}
+
+struct State {
+    font: Font,
+    msg: String,
+}
@@ -0,0 +1,20 @@
+use nae::prelude::*;
+
+#[nae::main]
+fn main() {
+    // Values known at runtime can be captured by the callbacks
+    let message = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "Hello World!".to_string());
+    let background = Color::new(0.1, 0.2, 0.3, 1.0);
+
+    nae::init_with(|app| Font::from_bytes(app, include_bytes!("assets/Ubuntu-B.ttf")).unwrap())
+        .draw(move |app, font| {
+            let draw = app.draw();
+            draw.begin(background);
+            draw.text(font, &message, 188.0, 260.0, 80.0);
+            draw.end();
+        })
+        .build()
+        .unwrap();
+}
@@ -0,0 +1,54 @@
+use nae::prelude::*;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct Level {
+    name: String,
+    bunnies: Vec<Position>,
+}
+
+#[derive(Deserialize)]
+struct Position {
+    x: f32,
+    y: f32,
+}
+
+#[nae::main]
+fn main() {
+    nae::init_with(|app| State {
+        font: app.load_resource("./examples/assets/Ubuntu-B.ttf").unwrap(),
+        bunny: app.load_resource("./examples/assets/bunny.png").unwrap(),
+        level: app.load_resource("./examples/assets/level.json").unwrap(),
+    })
+    .event(event)
+    .draw(draw)
+    .build()
+    .unwrap();
+}
+
+fn event(_app: &mut App, _state: &mut State, evt: Event) {
+    if let Event::ResourceFailed { file, error } = evt {
+        println!("Error loading {}: {}", file, error);
+    }
+}
+
+fn draw(app: &mut App, state: &mut State) {
+    let draw = app.draw();
+    draw.begin(Color::new(0.1, 0.2, 0.3, 1.0));
+
+    // The data is available once the file is loaded and parsed
+    if let Some(level) = state.level.data() {
+        draw.text(&state.font, &level.name, 10.0, 10.0, 20.0);
+        for pos in &level.bunnies {
+            draw.image(&state.bunny, pos.x, pos.y);
+        }
+    }
+
+    draw.end();
+}
+
+struct State {
+    font: Font,
+    bunny: Texture,
+    level: Data<Level>,
+}
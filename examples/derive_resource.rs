@@ -0,0 +1,47 @@
+use nae::prelude::*;
+
+/// Words of a text file, one resource created with the derive
+#[derive(Clone, Resource)]
+struct Words(ResourceCell<Vec<String>>);
+
+impl Words {
+    fn parse(_app: &mut App, data: Vec<u8>) -> Result<Vec<String>, String> {
+        let text = String::from_utf8(data).map_err(|e| e.to_string())?;
+        Ok(text.split_whitespace().map(|w| w.to_string()).collect())
+    }
+}
+
+#[nae::main]
+fn main() {
+    nae::init_with(|app| State {
+        font: app.load_resource("./examples/assets/Ubuntu-B.ttf").unwrap(),
+        words: app
+            .load_resource("./examples/assets/loremipsum.txt")
+            .unwrap(),
+    })
+    .draw(draw)
+    .build()
+    .unwrap();
+}
+
+fn draw(app: &mut App, state: &mut State) {
+    let draw = app.draw();
+    draw.begin(Color::new(0.1, 0.2, 0.3, 1.0));
+
+    if let Some(words) = state.words.data() {
+        let longest = words
+            .iter()
+            .max_by_key(|w| w.len())
+            .cloned()
+            .unwrap_or_default();
+        let text = format!("Words: {}\nLongest: {}", words.len(), longest);
+        draw.text(&state.font, &text, 10.0, 10.0, 20.0);
+    }
+
+    draw.end();
+}
+
+struct State {
+    font: Font,
+    words: Words,
+}
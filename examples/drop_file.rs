@@ -0,0 +1,65 @@
+use nae::prelude::*;
+
+#[nae::main]
+fn main() {
+    nae::init_with(|app| State {
+        font: Font::from_bytes(app, include_bytes!("assets/Ubuntu-B.ttf")).unwrap(),
+        texture: None,
+        hovering: false,
+        msg: "Drop a PNG file on the window".to_string(),
+    })
+    .draw(draw)
+    .event(event)
+    .build()
+    .unwrap();
+}
+
+fn event(app: &mut App, state: &mut State, evt: Event) {
+    match evt {
+        Event::FileHovered { .. } => state.hovering = true,
+        Event::FileHoverCancelled => state.hovering = false,
+        Event::FileDropped { path, data } => {
+            state.hovering = false;
+
+            // the web target sends the content of the file, native targets load it from the path
+            let texture = match data {
+                Some(data) => app.load_resource_from_bytes::<Texture>(&path, data),
+                _ => app.load_resource::<Texture>(&path),
+            };
+
+            match texture {
+                Ok(texture) => {
+                    state.texture = Some(texture);
+                    state.msg = path;
+                }
+                Err(e) => state.msg = e,
+            }
+        }
+        _ => {}
+    }
+}
+
+fn draw(app: &mut App, state: &mut State) {
+    let draw = app.draw();
+    let color = if state.hovering {
+        Color::new(0.2, 0.4, 0.6, 1.0)
+    } else {
+        Color::new(0.1, 0.2, 0.3, 1.0)
+    };
+    draw.begin(color);
+
+    if let Some(texture) = &state.texture {
+        draw.image(texture, 10.0, 50.0);
+    }
+
+    draw.color = Color::WHITE;
+    draw.text(&state.font, &state.msg, 10.0, 10.0, 20.0);
+    draw.end();
+}
+
+struct State {
+    font: Font,
+    texture: Option<Texture>,
+    hovering: bool,
+    msg: String,
+}
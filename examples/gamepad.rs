@@ -0,0 +1,71 @@
+use nae::prelude::*;
+
+#[nae::main]
+fn main() {
+    nae::init_with(|app| State::new(app))
+        .draw(draw)
+        .update(update)
+        .build()
+        .unwrap();
+}
+
+fn update(app: &mut App, state: &mut State) {
+    let pad = match app.gamepads.first() {
+        Some(pad) => pad,
+        _ => return,
+    };
+
+    let (x, y) = pad.left_stick();
+    state.x += x * 300.0 * app.delta;
+    state.y += y * 300.0 * app.delta;
+
+    if pad.was_pressed(GamepadButton::South) {
+        state.big = !state.big;
+    }
+}
+
+fn draw(app: &mut App, state: &mut State) {
+    let connected = app.gamepads.len();
+
+    let draw = app.draw();
+    draw.begin(Color::new(0.1, 0.2, 0.3, 1.0));
+
+    draw.color = Color::RED;
+    draw.circle(state.x, state.y, if state.big { 80.0 } else { 50.0 });
+
+    draw.color = Color::WHITE;
+    draw.text(
+        &state.font,
+        "Use the left stick to move the circle and South (A) to resize it.",
+        10.0,
+        10.0,
+        20.0,
+    );
+    draw.text(
+        &state.font,
+        &format!("Gamepads connected: {}", connected),
+        10.0,
+        560.0,
+        20.0,
+    );
+
+    draw.end();
+}
+
+struct State {
+    x: f32,
+    y: f32,
+    big: bool,
+    font: Font,
+}
+
+impl State {
+    fn new(app: &mut App) -> Self {
+        State {
+            font: Font::from_bytes(app, include_bytes!("assets/Ubuntu-B.ttf")).unwrap(),
+            x: 400.0,
+            y: 300.0,
+            big: false,
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use nae::prelude::*;
+
+#[nae::main]
+fn main() {
+    nae::init_with(|app| State {
+        font: app.load_resource("./examples/assets/Ubuntu-B.ttf").unwrap(),
+        texture: app.load_resource("./examples/assets/ferris.png").unwrap(),
+        msg: "Edit ./examples/assets/ferris.png and save it".to_string(),
+    })
+    .hot_reload(0.5)
+    .event(event)
+    .draw(draw)
+    .build()
+    .unwrap();
+}
+
+fn event(_app: &mut App, state: &mut State, evt: Event) {
+    match evt {
+        Event::ResourceReloaded { file } => {
+            state.msg = format!("Reloaded: {}", file);
+        }
+        Event::ResourceFailed { file, error } => {
+            state.msg = format!("Error on {}: {}", file, error);
+        }
+        _ => {}
+    }
+}
+
+fn draw(app: &mut App, state: &mut State) {
+    let draw = app.draw();
+    draw.begin(Color::new(0.1, 0.2, 0.3, 1.0));
+    draw.image(&state.texture, 200.0, 150.0);
+    draw.text(&state.font, &state.msg, 10.0, 10.0, 20.0);
+    draw.end();
+}
+
+struct State {
+    font: Font,
+    texture: Texture,
+    msg: String,
+}
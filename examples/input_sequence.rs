@@ -0,0 +1,57 @@
+use nae::prelude::*;
+
+#[nae::main]
+fn main() {
+    nae::init_with(|app| State::new(app))
+        .draw(draw)
+        .update(update)
+        .build()
+        .unwrap();
+}
+
+fn update(app: &mut App, state: &mut State) {
+    if app.input_buffer.sequence(&state.fireball) {
+        state.msg = "Fireball!".to_string();
+    } else if app.input_buffer.double_tap(KeyCode::Right, 0.3) {
+        state.msg = "Dash!".to_string();
+    } else if app.input_buffer.held_and_released(KeyCode::A, 1.0) {
+        state.msg = "Charged attack!".to_string();
+    }
+}
+
+fn draw(app: &mut App, state: &mut State) {
+    let draw = app.draw();
+    draw.begin(Color::new(0.1, 0.2, 0.3, 1.0));
+
+    draw.color = Color::WHITE;
+    draw.text(
+        &state.font,
+        "DOWN, DOWN+RIGHT, RIGHT+A: Fireball\nRIGHT, RIGHT: Dash\nHold A for a second and release: Charged attack",
+        10.0,
+        10.0,
+        20.0,
+    );
+
+    draw.color = Color::YELLOW;
+    draw.text(&state.font, &state.msg, 400.0, 300.0, 40.0);
+    draw.end();
+}
+
+struct State {
+    font: Font,
+    fireball: Sequence,
+    msg: String,
+}
+
+impl State {
+    fn new(app: &mut App) -> Self {
+        State {
+            font: Font::from_bytes(app, include_bytes!("assets/Ubuntu-B.ttf")).unwrap(),
+            fireball: Sequence::new(0.25)
+                .then(KeyCode::Down)
+                .then_all(vec![KeyCode::Down, KeyCode::Right])
+                .then_all(vec![KeyCode::Right, KeyCode::A]),
+            msg: "".to_string(),
+        }
+    }
+}
@@ -12,7 +12,7 @@ fn main() {
 
 fn event(app: &mut App, state: &mut State, evt: Event) {
     match evt {
-        Event::KeyDown { key } => {
+        Event::KeyDown { key, .. } => {
             state.last_key = Some(key);
         }
         _ => {}
@@ -17,7 +17,7 @@ fn event(app: &mut App, state: &mut State, evt: Event) {
         Event::ReceivedCharacter(c) if c != '\u{7f}' => {
             state.msg.push(c);
         }
-        Event::KeyDown { key } => match key {
+        Event::KeyDown { key, .. } => match key {
             KeyCode::Back => {
                 let _ = state.msg.pop();
             }
@@ -1,56 +1,63 @@
 use nae::prelude::*;
 
 struct State {
-    to_load: Vec<String>,
     font: Font,
     resources: Vec<Texture>,
-    time: f32,
+    ready: bool,
 }
 
 #[nae::main]
 fn main() {
     nae::init_with(init)
-        .update(update)
+        .event(event)
         .draw(draw)
         .build()
         .unwrap();
 }
 
 fn init(app: &mut App) -> State {
+    // All the textures are part of the same group to track the loading progress
+    let resources = file_names()
+        .iter()
+        .map(|file| {
+            app.load_resource_in("level", &format!("./examples/assets/{}", file))
+                .unwrap()
+        })
+        .collect();
+
     State {
-        to_load: file_names(),
         font: app.load_resource("./examples/assets/Ubuntu-B.ttf").unwrap(),
-        resources: Vec::with_capacity(20),
-        time: 0.0,
+        resources,
+        ready: false,
     }
 }
 
-fn update(app: &mut App, state: &mut State) {
-    // Load one texture each 0.5 seconds to do it slowly and have time to display the load bar
-    if state.time >= 0.5 {
-        state.time = 0.0;
-        if let Some(file) = state.to_load.pop() {
-            // Try to load the file
-            let texture = app
-                .load_resource(&format!("./examples/assets/{}", file))
-                .unwrap();
-            state.resources.push(texture);
+fn event(app: &mut App, state: &mut State, evt: Event) {
+    match evt {
+        Event::ResourceGroupReady { group } if group == "level" => {
+            state.ready = true;
+        }
+        Event::KeyDown {
+            key: KeyCode::U, ..
+        } if state.ready => {
+            // The textures are freed once the handles are dropped
+            app.unload_group("level");
+            state.resources.clear();
         }
+        _ => {}
     }
-
-    state.time += app.delta;
 }
 
 fn draw(app: &mut App, state: &mut State) {
+    let percent = app
+        .resource_group("level")
+        .map(|group| group.progress())
+        .unwrap_or(0.0);
+    let width = 400.0 * percent;
+
     let draw = app.draw();
     draw.begin(Color::ORANGE);
 
-    // Resource progress
-    let capacity = state.resources.capacity();
-    let loaded = loaded_length(&state.resources);
-    let percent = loaded as f32 / capacity as f32;
-    let width = 400.0 * percent;
-
     // Draw a load bar
     draw.rounded_rect(200.0, 280.0, 400.0, 40.0, 10.0);
     draw.color = Color::GREEN;
@@ -59,23 +66,19 @@ fn draw(app: &mut App, state: &mut State) {
     draw.stroke_rounded_rect(200.0, 280.0, 400.0, 40.0, 10.0, 10.0);
 
     // Draw the "All Loaded" message on top of the bar
-    if loaded == capacity {
+    if state.ready {
         draw.set_text_align(HorizontalAlign::Center, VerticalAlign::Center);
-        draw.text(&state.font, "All Loaded", 400.0, 300.0, 20.0);
+        let msg = if state.resources.is_empty() {
+            "Unloaded"
+        } else {
+            "All Loaded, press U to unload"
+        };
+        draw.text(&state.font, msg, 400.0, 300.0, 20.0);
     }
 
     draw.end();
 }
 
-// Count the number of textures are loaded
-fn loaded_length(resources: &[Texture]) -> usize {
-    resources
-        .iter()
-        .filter(|res| res.is_loaded())
-        .collect::<Vec<_>>()
-        .len()
-}
-
 fn file_names() -> Vec<String> {
     [
         "bunny.png",
@@ -0,0 +1,81 @@
+use nae::prelude::*;
+
+struct State {
+    font: Font,
+    x: f32,
+    y: f32,
+    size: f32,
+}
+
+#[nae::main]
+fn main() {
+    nae::init_with(init)
+        .draw(draw)
+        .update(update)
+        .build()
+        .unwrap();
+}
+
+fn init(app: &mut App) -> State {
+    State {
+        font: Font::from_bytes(app, include_bytes!("assets/Ubuntu-B.ttf")).unwrap(),
+        x: 400.0,
+        y: 300.0,
+        size: 50.0,
+    }
+}
+
+fn update(app: &mut App, state: &mut State) {
+    if app.keyboard.was_pressed(KeyCode::Space) {
+        let lock = !app.pointer_locked();
+        if let Err(e) = app.set_pointer_lock(lock) {
+            println!("{}", e);
+        }
+    }
+
+    // the circle follows the relative motion, it works also when the pointer is locked
+    let (motion_x, motion_y) = app.mouse.motion();
+    state.x = (state.x + motion_x).max(0.0).min(800.0);
+    state.y = (state.y + motion_y).max(0.0).min(600.0);
+
+    let (_, wheel_y) = app.mouse.wheel_delta();
+    state.size = (state.size + wheel_y * 5.0).max(10.0).min(200.0);
+}
+
+fn draw(app: &mut App, state: &mut State) {
+    let drag = if app.mouse.is_dragging(MouseButton::Left) {
+        app.mouse.drag_start(MouseButton::Left)
+    } else {
+        None
+    };
+    let (mx, my) = app.mouse.position();
+    let distance = app.mouse.drag_distance(MouseButton::Left);
+
+    let draw = app.draw();
+    draw.begin(Color::new(0.1, 0.2, 0.3, 1.0));
+
+    draw.color = Color::RED;
+    draw.circle(state.x, state.y, state.size);
+
+    if let Some((x, y)) = drag {
+        draw.color = Color::YELLOW;
+        draw.stroke_rect(x, y, mx - x, my - y, 2.0);
+        draw.text(
+            &state.font,
+            &format!("Drag distance: {:.0}", distance),
+            10.0,
+            560.0,
+            20.0,
+        );
+    }
+
+    draw.color = Color::WHITE;
+    draw.text(
+        &state.font,
+        "Move the mouse and the wheel, drag with the left button and press SPACE to lock the pointer",
+        10.0,
+        10.0,
+        16.0,
+    );
+    draw.end();
+}
@@ -0,0 +1,70 @@
+use nae::prelude::*;
+
+/// Toggle the fullscreen mode pressing F11 and hide the key to the app
+struct FullscreenPlugin;
+
+impl Plugin for FullscreenPlugin {
+    fn event(&mut self, app: &mut App, event: Event) -> Option<Event> {
+        match event {
+            Event::KeyDown {
+                key: KeyCode::F11,
+                is_repeat,
+                ..
+            } => {
+                if !is_repeat {
+                    let full = app.fullscreen();
+                    app.set_fullscreen(!full);
+                }
+                None
+            }
+            Event::KeyUp {
+                key: KeyCode::F11, ..
+            } => None,
+            _ => Some(event),
+        }
+    }
+}
+
+/// Count the number of frames drawn
+struct FrameCounter {
+    frames: u64,
+}
+
+impl Plugin for FrameCounter {
+    fn post_draw(&mut self, _app: &mut App) {
+        self.frames += 1;
+    }
+}
+
+#[nae::main]
+fn main() {
+    nae::init_with(init)
+        .plugin(FullscreenPlugin)
+        .plugin(FrameCounter { frames: 0 })
+        .event(event)
+        .draw(draw)
+        .build()
+        .unwrap();
+}
+
+fn init(app: &mut App) -> Font {
+    Font::from_bytes(app, include_bytes!("assets/Ubuntu-B.ttf")).unwrap()
+}
+
+fn event(_app: &mut App, _font: &mut Font, evt: Event) {
+    // F11 never reach this callback
+    if let Event::KeyDown { key, .. } = evt {
+        println!("Key down: {:?}", key);
+    }
+}
+
+fn draw(app: &mut App, font: &mut Font) {
+    let frames = app.plugin::<FrameCounter>().map_or(0, |p| p.frames);
+
+    let draw = app.draw();
+    draw.begin(Color::new(0.1, 0.2, 0.3, 1.0));
+    draw.set_text_align(HorizontalAlign::Center, VerticalAlign::Center);
+    draw.text(font, "Press F11 to toggle fullscreen", 400.0, 260.0, 30.0);
+    draw.text(font, &format!("Frames: {}", frames), 400.0, 320.0, 30.0);
+    draw.end();
+}
@@ -0,0 +1,53 @@
+use nae::prelude::*;
+
+#[nae::main]
+fn main() {
+    nae::init_with(|app| State {
+        bunny: Texture::from_bytes(app, include_bytes!("assets/bunny.png")).unwrap(),
+        count: 1000,
+    })
+    .plugin(ProfilerOverlay::new(include_bytes!("assets/Ubuntu-B.ttf")))
+    .update(update)
+    .draw(draw)
+    .build()
+    .unwrap();
+}
+
+fn update(app: &mut App, state: &mut State) {
+    if app.keyboard.was_pressed(KeyCode::Up) {
+        state.count += 1000;
+    }
+
+    if app.keyboard.was_pressed(KeyCode::Down) && state.count >= 1000 {
+        state.count -= 1000;
+    }
+
+    if let Some(frame) = app.profiler.last() {
+        if frame.frame > 33.3 {
+            log::warn!("Slow frame: {:.2}ms", frame.frame);
+        }
+    }
+}
+
+fn draw(app: &mut App, state: &mut State) {
+    let width = app.width();
+    let height = app.height();
+    let time = app.time;
+
+    let draw = app.draw();
+    draw.begin(Color::new(0.1, 0.2, 0.3, 1.0));
+
+    for i in 0..state.count {
+        let n = i as f32;
+        let x = (n * 7.3 + time * 50.0) % width;
+        let y = (n * 13.7) % height;
+        draw.image(&state.bunny, x, y);
+    }
+
+    draw.end();
+}
+
+struct State {
+    bunny: Texture,
+    count: usize,
+}
@@ -0,0 +1,65 @@
+use nae::prelude::*;
+
+struct State {
+    font: Font,
+    record: Option<InputRecord>,
+    points: Vec<(f32, f32)>,
+}
+
+#[nae::main]
+fn main() {
+    nae::init_with(init)
+        .update(update)
+        .draw(draw)
+        .build()
+        .unwrap();
+}
+
+fn init(app: &mut App) -> State {
+    State {
+        font: Font::from_bytes(app, include_bytes!("assets/Ubuntu-B.ttf")).unwrap(),
+        record: None,
+        points: vec![],
+    }
+}
+
+fn update(app: &mut App, state: &mut State) {
+    if app.keyboard.was_pressed(KeyCode::R) {
+        if app.is_recording() {
+            state.record = app.stop_recording();
+        } else {
+            state.points.clear();
+            app.start_recording();
+        }
+    }
+
+    if app.keyboard.was_pressed(KeyCode::P) && !app.is_recording() {
+        if let Some(record) = state.record.clone() {
+            state.points.clear();
+            app.replay(record);
+        }
+    }
+
+    if app.mouse.is_down(MouseButton::Left) {
+        state.points.push((app.mouse.x, app.mouse.y));
+    }
+}
+
+fn draw(app: &mut App, state: &mut State) {
+    let status = if app.is_recording() {
+        "Recording... (R to stop)"
+    } else if app.is_replaying() {
+        "Replaying..."
+    } else {
+        "Press R to record, P to replay"
+    };
+
+    let draw = app.draw();
+    draw.begin(Color::new(0.1, 0.2, 0.3, 1.0));
+    state
+        .points
+        .iter()
+        .for_each(|(x, y)| draw.circle(*x, *y, 4.0));
+    draw.text(&state.font, status, 10.0, 10.0, 24.0);
+    draw.end();
+}
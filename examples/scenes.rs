@@ -0,0 +1,118 @@
+use nae::prelude::*;
+
+#[nae::main]
+fn main() {
+    nae::init_with(|app| SceneManager::new(app, Menu::new(app)))
+        .scenes()
+        .build()
+        .unwrap();
+}
+
+fn load_font(app: &mut App) -> Font {
+    Font::from_bytes(app, include_bytes!("assets/Ubuntu-B.ttf")).unwrap()
+}
+
+struct Menu {
+    font: Font,
+}
+
+impl Menu {
+    fn new(app: &mut App) -> Self {
+        Self {
+            font: load_font(app),
+        }
+    }
+}
+
+impl Scene for Menu {
+    fn update(&mut self, app: &mut App) -> SceneAction {
+        if app.keyboard.was_pressed(KeyCode::Space) {
+            return SceneAction::replace(Game::new(app))
+                .with_effect(FadeEffect::new(Color::BLACK, 1.0));
+        }
+
+        SceneAction::None
+    }
+
+    fn draw(&mut self, app: &mut App) {
+        let draw = app.draw();
+        draw.begin(Color::new(0.1, 0.2, 0.3, 1.0));
+        draw.set_text_align(HorizontalAlign::Center, VerticalAlign::Center);
+        draw.text(&self.font, "Press SPACE to start", 400.0, 300.0, 40.0);
+        draw.end();
+    }
+}
+
+struct Game {
+    font: Font,
+    x: f32,
+}
+
+impl Game {
+    fn new(app: &mut App) -> Self {
+        Self {
+            font: load_font(app),
+            x: 0.0,
+        }
+    }
+}
+
+impl Scene for Game {
+    fn update(&mut self, app: &mut App) -> SceneAction {
+        self.x = (self.x + 200.0 * app.delta) % 800.0;
+
+        if app.keyboard.was_pressed(KeyCode::Escape) {
+            return SceneAction::push(Pause::new(app));
+        }
+
+        SceneAction::None
+    }
+
+    fn draw(&mut self, app: &mut App) {
+        let draw = app.draw();
+        draw.begin(Color::ORANGE);
+        draw.color = Color::WHITE;
+        draw.circle(self.x, 300.0, 40.0);
+        draw.set_text_align(HorizontalAlign::Left, VerticalAlign::Top);
+        draw.text(&self.font, "Press ESC to pause", 10.0, 10.0, 20.0);
+        draw.end();
+    }
+
+    // Keep the game visible below the pause screen
+    fn draw_when_paused(&self) -> bool {
+        true
+    }
+}
+
+struct Pause {
+    font: Font,
+}
+
+impl Pause {
+    fn new(app: &mut App) -> Self {
+        Self {
+            font: load_font(app),
+        }
+    }
+}
+
+impl Scene for Pause {
+    fn update(&mut self, app: &mut App) -> SceneAction {
+        if app.keyboard.was_pressed(KeyCode::Escape) {
+            return SceneAction::Pop;
+        }
+
+        SceneAction::None
+    }
+
+    fn draw(&mut self, app: &mut App) {
+        let draw = app.draw();
+        draw.begin_without_clear();
+        draw.color = Color::BLACK.with_alpha(0.6);
+        draw.rect(0.0, 0.0, 800.0, 600.0);
+        draw.color = Color::WHITE;
+        draw.set_text_align(HorizontalAlign::Center, VerticalAlign::Center);
+        draw.text(&self.font, "Paused", 400.0, 300.0, 60.0);
+        draw.end();
+    }
+}
@@ -0,0 +1,85 @@
+use nae::prelude::*;
+
+#[nae::main]
+fn main() {
+    nae::init_with(|app| State::new(app))
+        .draw(draw)
+        .update(update)
+        .build()
+        .unwrap();
+}
+
+fn update(app: &mut App, state: &mut State) {
+    state.scale *= app.gestures.pinch();
+    state.rotation += app.gestures.rotation();
+
+    if let Some((x, y)) = app.gestures.double_tap() {
+        state.x = x;
+        state.y = y;
+    }
+
+    if let Some(gesture) = app.gestures.iter().last() {
+        state.last_gesture = Some(*gesture);
+    }
+}
+
+fn draw(app: &mut App, state: &mut State) {
+    let touches: Vec<(f32, f32)> = app.touches.iter().map(|t| (t.x, t.y)).collect();
+
+    let draw = app.draw();
+    draw.begin(Color::new(0.1, 0.2, 0.3, 1.0));
+
+    draw.color = Color::RED;
+    draw.push_translation(state.x, state.y);
+    draw.push_rotation(state.rotation);
+    draw.push_scale(state.scale, state.scale);
+    draw.rect(-50.0, -50.0, 100.0, 100.0);
+    draw.pop();
+    draw.pop();
+    draw.pop();
+
+    draw.color = Color::WHITE;
+    touches.iter().for_each(|(x, y)| draw.circle(*x, *y, 30.0));
+
+    draw.text(
+        &state.font,
+        "Pinch and rotate to transform the square, double tap to move it.",
+        10.0,
+        10.0,
+        20.0,
+    );
+
+    if let Some(gesture) = &state.last_gesture {
+        draw.text(
+            &state.font,
+            &format!("Last gesture: {:?}", gesture),
+            10.0,
+            560.0,
+            20.0,
+        );
+    }
+
+    draw.end();
+}
+
+struct State {
+    x: f32,
+    y: f32,
+    scale: f32,
+    rotation: f32,
+    last_gesture: Option<Gesture>,
+    font: Font,
+}
+
+impl State {
+    fn new(app: &mut App) -> Self {
+        State {
+            font: Font::from_bytes(app, include_bytes!("assets/Ubuntu-B.ttf")).unwrap(),
+            x: 400.0,
+            y: 300.0,
+            scale: 1.0,
+            rotation: 0.0,
+            last_gesture: None,
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use nae::prelude::*;
+
+#[nae::main]
+fn main() {
+    nae::init_with(init).draw(draw).build().unwrap();
+}
+
+fn init(app: &mut App) -> State {
+    // The assets are embedded on the binary
+    app.vfs.mount_embedded(
+        "",
+        embed_files!("assets", ["bunny.png", "ferris.png", "Ubuntu-B.ttf"]),
+        0,
+    );
+
+    // Files inside ./mods override the embedded ones, (e.g. ./mods/bunny.png)
+    app.vfs.mount_dir("", "./mods", 10);
+
+    State {
+        font: app.load_resource("Ubuntu-B.ttf").unwrap(),
+        bunny: app.load_resource("bunny.png").unwrap(),
+        ferris: app.load_resource("./ferris.png").unwrap(),
+    }
+}
+
+fn draw(app: &mut App, state: &mut State) {
+    let draw = app.draw();
+    draw.begin(Color::new(0.1, 0.2, 0.3, 1.0));
+
+    draw.image(&state.bunny, 100.0, 200.0);
+    draw.image(&state.ferris, 300.0, 200.0);
+
+    draw.text(
+        &state.font,
+        "Put a bunny.png or ferris.png inside ./mods to override them",
+        10.0,
+        10.0,
+        20.0,
+    );
+
+    draw.end();
+}
+
+struct State {
+    font: Font,
+    bunny: Texture,
+    ferris: Texture,
+}
@@ -0,0 +1,113 @@
+use nae::prelude::*;
+
+const CURSORS: [CursorIcon; 11] = [
+    CursorIcon::Default,
+    CursorIcon::Crosshair,
+    CursorIcon::Hand,
+    CursorIcon::Text,
+    CursorIcon::Move,
+    CursorIcon::Wait,
+    CursorIcon::NotAllowed,
+    CursorIcon::ResizeHorizontal,
+    CursorIcon::ResizeVertical,
+    CursorIcon::ResizeNESW,
+    CursorIcon::ResizeNWSE,
+];
+
+#[nae::main]
+fn main() {
+    nae::init_with(|app| State::new(app))
+        .draw(draw)
+        .event(event)
+        .build()
+        .unwrap();
+}
+
+fn event(app: &mut App, state: &mut State, evt: Event) {
+    match evt {
+        Event::KeyDown { key, .. } => match key {
+            KeyCode::Space => {
+                state.cursor = (state.cursor + 1) % CURSORS.len();
+                app.set_cursor(CURSORS[state.cursor]);
+                app.set_title(&format!("Cursor: {:?}", CURSORS[state.cursor]));
+            }
+            KeyCode::H => {
+                let visible = app.cursor_visible();
+                app.set_cursor_visible(!visible);
+            }
+            KeyCode::C => {
+                if let Some(image) = state.bunny.to_rgba_image() {
+                    if let Err(e) = app.set_custom_cursor(image, 0, 0) {
+                        println!("{}", e);
+                    }
+                }
+            }
+            KeyCode::I => {
+                if let Err(e) = app.set_icon_from_texture(&state.bunny) {
+                    println!("{}", e);
+                }
+            }
+            KeyCode::S => {
+                let size = if app.width() as i32 == 800 {
+                    (640, 480)
+                } else {
+                    (800, 600)
+                };
+                app.set_size(size.0, size.1);
+            }
+            KeyCode::Right => {
+                let (x, y) = app.position();
+                app.set_position(x + 50, y);
+            }
+            KeyCode::Left => {
+                let (x, y) = app.position();
+                app.set_position(x - 50, y);
+            }
+            _ => {}
+        },
+        Event::WindowMove { x, y } => {
+            state.msg = format!("Window moved to {}x{}", x, y);
+        }
+        Event::WindowResize { width, height } => {
+            state.msg = format!("Window resized to {}x{}", width, height);
+        }
+        _ => {}
+    }
+}
+
+fn draw(app: &mut App, state: &mut State) {
+    let draw = app.draw();
+    draw.begin(Color::new(0.1, 0.2, 0.3, 1.0));
+
+    draw.color = Color::WHITE;
+    draw.text(
+        &state.font,
+        "SPACE: change cursor\nH: show/hide cursor\nC: custom cursor\nI: set icon\nS: change size\nLEFT/RIGHT: move window",
+        10.0,
+        10.0,
+        20.0,
+    );
+
+    draw.color = Color::YELLOW;
+    draw.text(&state.font, &state.msg, 10.0, 200.0, 20.0);
+
+    draw.end();
+}
+
+struct State {
+    font: Font,
+    bunny: Texture,
+    cursor: usize,
+    msg: String,
+}
+
+impl State {
+    fn new(app: &mut App) -> Self {
+        State {
+            font: Font::from_bytes(app, include_bytes!("assets/Ubuntu-B.ttf")).unwrap(),
+            bunny: Texture::from_bytes(app, include_bytes!("assets/bunny.png")).unwrap(),
+            cursor: 0,
+            msg: "".to_string(),
+        }
+    }
+}
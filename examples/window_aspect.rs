@@ -0,0 +1,39 @@
+use nae::prelude::*;
+
+#[nae::main]
+fn main() {
+    nae::init_with(init)
+        .draw(draw)
+        .size(800, 600)
+        .resizable()
+        .keep_window_aspect()
+        .build()
+        .unwrap();
+}
+
+fn init(app: &mut App) -> Font {
+    Font::from_bytes(app, include_bytes!("assets/Ubuntu-B.ttf")).unwrap()
+}
+
+fn draw(app: &mut App, font: &mut Font) {
+    let ww = app.width();
+    let hh = app.height();
+    let (mx, my) = (app.mouse.x, app.mouse.y);
+
+    let draw = app.draw();
+    draw.begin(Color::new(0.1, 0.2, 0.3, 1.0));
+
+    draw.color = Color::RED;
+    draw.stroke_rect(10.0, 10.0, ww - 20.0, hh - 20.0, 4.0);
+    draw.circle(mx, my, 10.0);
+
+    draw.color = Color::WHITE;
+    draw.text(
+        font,
+        &format!("Resize the window, the drawable area is {}x{}", ww, hh),
+        30.0,
+        30.0,
+        20.0,
+    );
+    draw.end();
+}
@@ -18,6 +18,18 @@ pub(crate) trait ToNaeValue {
     fn to_nae(&self) -> Self::Kind;
 }
 
+/// Returns the position and size of the biggest centered area with the aspect ratio given
+pub(crate) fn letterbox(width: i32, height: i32, aspect: f32) -> (i32, i32, i32, i32) {
+    let (w, h) = (width as f32, height as f32);
+    if w / h > aspect {
+        let area_width = (h * aspect).round() as i32;
+        ((width - area_width) / 2, 0, area_width, height)
+    } else {
+        let area_height = (w / aspect).round() as i32;
+        (0, (height - area_height) / 2, width, area_height)
+    }
+}
+
 /// Read the content of a file and return a future with the content
 #[cfg(not(target_arch = "wasm32"))]
 pub fn load_file(path: &str) -> impl Future<Item = Vec<u8>, Error = String> {
@@ -111,3 +123,29 @@ pub fn load_file(path: &str) -> impl Future<Item = Vec<u8>, Error = String> {
         })
     })
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::*;
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_letterbox() {
+        // wider than the aspect ratio
+        assert_eq!(letterbox(1000, 600, 4.0 / 3.0), (100, 0, 800, 600));
+        // taller than the aspect ratio
+        assert_eq!(letterbox(800, 800, 4.0 / 3.0), (0, 100, 800, 600));
+        assert_eq!(letterbox(800, 600, 4.0 / 3.0), (0, 0, 800, 600));
+
+        // the logical area scaled by a dpi of 2 covers the same physical area
+        let (x, y, width, height) = letterbox(1600 / 2, 1200 / 2, 16.0 / 9.0);
+        assert_eq!((x, y, width, height), (0, 75, 800, 450));
+        assert_eq!(
+            (x * 2, y * 2, width * 2, height * 2),
+            letterbox(1600, 1200, 16.0 / 9.0)
+        );
+    }
+}
@@ -0,0 +1,345 @@
+use glutin::dpi::PhysicalSize;
+use glutin::{ContextBuilder, PossiblyCurrent};
+use nae_core::window::{BaseWindow, CursorIcon, RgbaImage};
+use nae_core::{BaseApp, BaseSystem, BuilderOpts, Event, EventIterator};
+use nae_gfx::HeadlessDevice;
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+)))]
+use glutin::event_loop::EventLoop;
+
+pub struct System {
+    window: Window,
+    draw: nae_gfx::Draw,
+    events: EventIterator,
+    clipboard: Option<String>,
+}
+
+impl BaseSystem for System {
+    type Kind = Self;
+    type Graphics = nae_gfx::Graphics;
+    type Draw = nae_gfx::Draw;
+    type Window = Window;
+
+    fn new(opts: BuilderOpts) -> Result<Self, String> {
+        let win = Window::new(&opts)?;
+        let draw = nae_gfx::Draw::new(&win.device)?;
+        Ok(Self {
+            window: win,
+            events: EventIterator::new(),
+            clipboard: None,
+            draw,
+        })
+    }
+
+    fn window(&mut self) -> &mut Self::Window {
+        &mut self.window
+    }
+
+    fn gfx(&mut self) -> &mut Self::Graphics {
+        &mut self.draw.gfx
+    }
+
+    fn draw(&mut self) -> &mut Self::Draw {
+        &mut self.draw
+    }
+
+    fn events(&mut self) -> &mut EventIterator {
+        &mut self.events
+    }
+
+    fn width(&self) -> f32 {
+        self.window.width() as _
+    }
+
+    fn height(&self) -> f32 {
+        self.window.height() as _
+    }
+
+    fn dpi(&self) -> f32 {
+        self.window.dpi()
+    }
+
+    fn set_fullscreen(&mut self, full: bool) {
+        self.window.fullscreen = full;
+    }
+
+    fn fullscreen(&self) -> bool {
+        self.window.fullscreen()
+    }
+
+    // in-memory clipboard to not depend on the system clipboard on tests
+    fn clipboard_text(&mut self) -> Option<String> {
+        self.clipboard.clone()
+    }
+
+    fn set_clipboard_text(&mut self, text: &str) -> Result<(), String> {
+        self.clipboard = Some(text.to_string());
+        Ok(())
+    }
+}
+
+/// Fake window backed by an offscreen GL context
+pub struct Window {
+    device: HeadlessDevice,
+    title: String,
+    fullscreen: bool,
+    position: (i32, i32),
+    cursor_visible: bool,
+    cursor: CursorIcon,
+    pointer_locked: bool,
+
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )))]
+    _event_loop: EventLoop<()>,
+}
+
+impl Window {
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    fn new(opts: &BuilderOpts) -> Result<Self, String> {
+        use glutin::platform::unix::HeadlessContextExt;
+
+        // OSMesa doesn't need a display server so it works on CI machines
+        let size = PhysicalSize::new(opts.width as u32, opts.height as u32);
+        let ctx = context_builder()
+            .build_osmesa(size)
+            .map_err(|e| format!("{}", e))?;
+
+        Ok(Self {
+            device: make_device(ctx, opts)?,
+            title: opts.title.to_string(),
+            fullscreen: opts.fullscreen,
+            position: (0, 0),
+            cursor_visible: true,
+            cursor: CursorIcon::Default,
+            pointer_locked: false,
+        })
+    }
+
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )))]
+    fn new(opts: &BuilderOpts) -> Result<Self, String> {
+        let event_loop = EventLoop::new();
+        let size = PhysicalSize::new(opts.width as u32, opts.height as u32);
+        let ctx = context_builder()
+            .build_headless(&event_loop, size)
+            .map_err(|e| format!("{}", e))?;
+
+        Ok(Self {
+            device: make_device(ctx, opts)?,
+            title: opts.title.to_string(),
+            fullscreen: opts.fullscreen,
+            position: (0, 0),
+            cursor_visible: true,
+            cursor: CursorIcon::Default,
+            pointer_locked: false,
+            _event_loop: event_loop,
+        })
+    }
+}
+
+fn context_builder<'a>() -> ContextBuilder<'a, glutin::NotCurrent> {
+    ContextBuilder::new()
+        .with_gl(glutin::GlRequest::GlThenGles {
+            opengl_version: (3, 3),
+            opengles_version: (2, 0),
+        })
+        .with_gl_profile(glutin::GlProfile::Core)
+}
+
+fn make_device(
+    ctx: glutin::Context<glutin::NotCurrent>,
+    opts: &BuilderOpts,
+) -> Result<HeadlessDevice, String> {
+    let ctx: glutin::Context<PossiblyCurrent> =
+        unsafe { ctx.make_current() }.map_err(|(_, e)| format!("{}", e))?;
+
+    Ok(HeadlessDevice {
+        ctx,
+        width: opts.width,
+        height: opts.height,
+    })
+}
+
+impl BaseWindow for Window {
+    fn width(&self) -> i32 {
+        self.device.width
+    }
+
+    fn height(&self) -> i32 {
+        self.device.height
+    }
+
+    fn fullscreen(&self) -> bool {
+        self.fullscreen
+    }
+
+    fn title(&self) -> &str {
+        &self.title
+    }
+
+    fn dpi(&self) -> f32 {
+        1.0
+    }
+
+    fn position(&self) -> (i32, i32) {
+        self.position
+    }
+
+    fn cursor_visible(&self) -> bool {
+        self.cursor_visible
+    }
+
+    fn cursor(&self) -> CursorIcon {
+        self.cursor
+    }
+
+    fn pointer_locked(&self) -> bool {
+        self.pointer_locked
+    }
+
+    fn set_title(&mut self, title: &str) {
+        self.title = title.to_string();
+    }
+
+    // the offscreen buffer keeps its size, only the reported size changes
+    fn set_size(&mut self, width: i32, height: i32) {
+        self.device.width = width;
+        self.device.height = height;
+    }
+
+    fn set_position(&mut self, x: i32, y: i32) {
+        self.position = (x, y);
+    }
+
+    fn set_icon(&mut self, _icon: Option<RgbaImage>) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn set_cursor_visible(&mut self, visible: bool) {
+        self.cursor_visible = visible;
+    }
+
+    fn set_cursor(&mut self, cursor: CursorIcon) {
+        if cursor != CursorIcon::Custom {
+            self.cursor = cursor;
+        }
+    }
+
+    fn set_custom_cursor(
+        &mut self,
+        _image: RgbaImage,
+        _hot_x: u32,
+        _hot_y: u32,
+    ) -> Result<(), String> {
+        self.cursor = CursorIcon::Custom;
+        Ok(())
+    }
+
+    fn set_pointer_lock(&mut self, lock: bool) -> Result<(), String> {
+        self.pointer_locked = lock;
+        Ok(())
+    }
+}
+
+/// Drives the app frame by frame without an event loop.
+/// Events must be pushed manually using `push_event` before calling `step`
+pub struct HeadlessRunner<A, S> {
+    app: A,
+    state: S,
+    update: Box<dyn FnMut(&mut A, &mut S)>,
+    draw: Box<dyn FnMut(&mut A, &mut S)>,
+    running: bool,
+}
+
+impl<A, S> HeadlessRunner<A, S>
+where
+    A: BaseApp<System = System> + 'static,
+    S: 'static,
+{
+    pub fn new<F, D>(app: A, state: S, update: F, draw: D) -> Self
+    where
+        F: FnMut(&mut A, &mut S) + 'static,
+        D: FnMut(&mut A, &mut S) + 'static,
+    {
+        Self {
+            app,
+            state,
+            update: Box::new(update),
+            draw: Box::new(draw),
+            running: true,
+        }
+    }
+
+    pub fn app(&mut self) -> &mut A {
+        &mut self.app
+    }
+
+    pub fn state(&mut self) -> &mut S {
+        &mut self.state
+    }
+
+    /// Queue an event to be processed on the next step
+    pub fn push_event(&mut self, evt: Event) {
+        self.app.system().events.push(evt);
+    }
+
+    /// Returns false once an `Event::Quit` was processed
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Run one frame, update and draw. Returns if the app is still running
+    pub fn step(&mut self) -> bool {
+        if !self.running {
+            return false;
+        }
+
+        let quit = self
+            .app
+            .system()
+            .events
+            .iter()
+            .any(|evt| *evt == Event::Quit);
+        if quit {
+            self.running = false;
+        }
+
+        (self.update)(&mut self.app, &mut self.state);
+        (self.draw)(&mut self.app, &mut self.state);
+        self.running
+    }
+}
+
+pub fn run<A, S, F, D>(app: A, state: S, update: F, draw: D) -> Result<(), String>
+where
+    A: BaseApp<System = System> + 'static,
+    S: 'static,
+    F: FnMut(&mut A, &mut S) + 'static,
+    D: FnMut(&mut A, &mut S) + 'static,
+{
+    let mut runner = HeadlessRunner::new(app, state, update, draw);
+    while runner.step() {}
+    Ok(())
+}
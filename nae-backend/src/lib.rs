@@ -3,18 +3,40 @@ pub use common::*;
 pub use nae_core::*;
 pub use nae_gfx::*;
 
-#[cfg(all(not(target_arch = "wasm32"), feature = "winit"))]
+#[cfg(all(
+    not(target_arch = "wasm32"),
+    feature = "winit",
+    not(feature = "headless")
+))]
 mod winit_backend;
 
-#[cfg(all(not(target_arch = "wasm32"), feature = "winit"))]
+#[cfg(all(
+    not(target_arch = "wasm32"),
+    feature = "winit",
+    not(feature = "headless")
+))]
 pub use winit_backend::*;
 
-#[cfg(all(not(target_arch = "wasm32"), feature = "sdl"))]
+#[cfg(all(
+    not(target_arch = "wasm32"),
+    feature = "sdl",
+    not(feature = "headless")
+))]
 mod sdl_backend;
 
-#[cfg(all(not(target_arch = "wasm32"), feature = "sdl"))]
+#[cfg(all(
+    not(target_arch = "wasm32"),
+    feature = "sdl",
+    not(feature = "headless")
+))]
 pub use sdl_backend::*;
 
+#[cfg(all(not(target_arch = "wasm32"), feature = "headless"))]
+mod headless_backend;
+
+#[cfg(all(not(target_arch = "wasm32"), feature = "headless"))]
+pub use headless_backend::*;
+
 #[cfg(target_arch = "wasm32")]
 mod web_backend;
 
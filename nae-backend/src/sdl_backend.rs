@@ -1,39 +1,64 @@
+use crate::common::letterbox;
 use crate::ToNaeValue;
 use futures::{future, Future};
-use nae_core::window::BaseWindow;
-use nae_core::{BaseApp, BuilderOpts, Event, KeyCode, MouseButton};
+use nae_core::log;
+use nae_core::window::{BaseWindow, CursorIcon, RgbaImage};
+use nae_core::{
+    BaseApp, BuilderOpts, Event, GamepadAxis, GamepadButton, KeyCode, Modifiers, MouseButton,
+};
 use nae_core::{BaseSystem, EventIterator};
-use sdl2::keyboard::{Keycode as SdlKeycode, Scancode};
-use sdl2::mouse::MouseButton as SdlMouseButton;
-use sdl2::video::{FullscreenType, Window as SdlWindow};
+use sdl2::controller::{Axis as SdlAxis, Button as SdlButton, GameController};
+use sdl2::keyboard::{Keycode as SdlKeycode, Mod, Scancode};
+use sdl2::mouse::{Cursor, MouseButton as SdlMouseButton, SystemCursor};
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::surface::Surface;
+use sdl2::video::{FullscreenType, Window as SdlWindow, WindowPos};
 use sdl2::{Sdl, VideoSubsystem};
 use std::cell::{RefCell, RefMut};
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::Read;
 use std::path::Path;
 use std::rc::Rc;
+use std::thread;
+use std::time::{Duration, Instant};
 
 pub struct System {
     window: Window,
     events: EventIterator,
     draw: nae_gfx::Draw,
+    fps_target: Option<i32>,
 }
 
 impl BaseSystem for System {
     type Kind = Self;
     type Graphics = nae_gfx::Graphics;
     type Draw = nae_gfx::Draw;
+    type Window = Window;
 
     fn new(mut opts: BuilderOpts) -> Result<Self, String> {
         let win = Window::new(&opts)?;
-        let draw = nae_gfx::Draw::new(&win.win)?;
+        let mut draw = nae_gfx::Draw::new(&win.win)?;
+        // the size and offset are scaled by the dpi so it must be set first
+        draw.update_dpi(win.dpi);
+        if win.aspect.is_some() {
+            let (x, y, width, height) = win.drawable_area();
+            draw.set_size(width as _, height as _);
+            draw.set_offset(x as _, y as _);
+        }
+
         Ok(Self {
             window: win,
             events: EventIterator::new(),
+            fps_target: opts.fps_target,
             draw,
         })
     }
 
+    fn window(&mut self) -> &mut Self::Window {
+        &mut self.window
+    }
+
     fn gfx(&mut self) -> &mut Self::Graphics {
         &mut self.draw.gfx
     }
@@ -65,6 +90,19 @@ impl BaseSystem for System {
     fn fullscreen(&self) -> bool {
         self.window.fullscreen()
     }
+
+    fn clipboard_text(&mut self) -> Option<String> {
+        let clipboard = self.window.video.clipboard();
+        if !clipboard.has_clipboard_text() {
+            return None;
+        }
+
+        clipboard.clipboard_text().ok()
+    }
+
+    fn set_clipboard_text(&mut self, text: &str) -> Result<(), String> {
+        self.window.video.clipboard().set_clipboard_text(text)
+    }
 }
 
 pub struct Window {
@@ -72,6 +110,10 @@ pub struct Window {
     video: VideoSubsystem,
     win: SdlWindow,
     dpi: f32,
+    cursor: CursorIcon,
+    // sdl resets the cursor when it's dropped
+    sdl_cursor: Option<Cursor>,
+    aspect: Option<f32>,
 }
 
 impl Window {
@@ -105,6 +147,11 @@ impl Window {
             win.set_maximum_size(width as _, height as _);
         }
 
+        if let Some(icon) = &opts.icon {
+            let mut data = icon.data.clone();
+            win.set_icon(sdl_surface(&mut data, icon.width, icon.height)?);
+        }
+
         let dpi = {
             let (w, _) = win.size();
             let (dw, _) = win.drawable_size();
@@ -116,9 +163,25 @@ impl Window {
             video,
             win,
             dpi,
+            cursor: CursorIcon::Default,
+            sdl_cursor: None,
+            aspect: if opts.keep_aspect {
+                Some(opts.width as f32 / opts.height as f32)
+            } else {
+                None
+            },
         })
     }
 
+    /// Logical position and size of the area used to draw
+    fn drawable_area(&self) -> (i32, i32, i32, i32) {
+        let (width, height) = self.win.size();
+        match self.aspect {
+            Some(aspect) => letterbox(width as _, height as _, aspect),
+            _ => (0, 0, width as _, height as _),
+        }
+    }
+
     fn set_fullscreen(&mut self, full: bool) {
         let state = if full {
             FullscreenType::True
@@ -131,13 +194,11 @@ impl Window {
 
 impl BaseWindow for Window {
     fn width(&self) -> i32 {
-        let (width, _) = self.win.size();
-        width as _
+        self.drawable_area().2
     }
 
     fn height(&self) -> i32 {
-        let (_, height) = self.win.size();
-        height as _
+        self.drawable_area().3
     }
 
     fn fullscreen(&self) -> bool {
@@ -154,6 +215,107 @@ impl BaseWindow for Window {
     fn dpi(&self) -> f32 {
         self.dpi
     }
+
+    fn position(&self) -> (i32, i32) {
+        self.win.position()
+    }
+
+    fn cursor_visible(&self) -> bool {
+        self.sdl.mouse().is_cursor_showing()
+    }
+
+    fn cursor(&self) -> CursorIcon {
+        self.cursor
+    }
+
+    fn pointer_locked(&self) -> bool {
+        self.sdl.mouse().relative_mouse_mode()
+    }
+
+    fn set_title(&mut self, title: &str) {
+        if let Err(e) = self.win.set_title(title) {
+            log::error!("{}", e);
+        }
+    }
+
+    fn set_size(&mut self, width: i32, height: i32) {
+        if let Err(e) = self.win.set_size(width as _, height as _) {
+            log::error!("{}", e);
+        }
+    }
+
+    fn set_position(&mut self, x: i32, y: i32) {
+        self.win
+            .set_position(WindowPos::Positioned(x), WindowPos::Positioned(y));
+    }
+
+    fn set_icon(&mut self, icon: Option<RgbaImage>) -> Result<(), String> {
+        // sdl can't remove the icon, a transparent one is used instead
+        let icon = icon.unwrap_or_else(|| RgbaImage {
+            width: 1,
+            height: 1,
+            data: vec![0; 4],
+        });
+
+        let mut data = icon.data;
+        self.win
+            .set_icon(sdl_surface(&mut data, icon.width, icon.height)?);
+        Ok(())
+    }
+
+    fn set_cursor_visible(&mut self, visible: bool) {
+        self.sdl.mouse().show_cursor(visible);
+    }
+
+    fn set_cursor(&mut self, cursor: CursorIcon) {
+        let system_cursor = match cursor {
+            CursorIcon::Default => SystemCursor::Arrow,
+            CursorIcon::Crosshair => SystemCursor::Crosshair,
+            CursorIcon::Hand => SystemCursor::Hand,
+            CursorIcon::Text => SystemCursor::IBeam,
+            CursorIcon::Move => SystemCursor::SizeAll,
+            CursorIcon::Wait => SystemCursor::Wait,
+            CursorIcon::NotAllowed => SystemCursor::No,
+            CursorIcon::ResizeHorizontal => SystemCursor::SizeWE,
+            CursorIcon::ResizeVertical => SystemCursor::SizeNS,
+            CursorIcon::ResizeNESW => SystemCursor::SizeNESW,
+            CursorIcon::ResizeNWSE => SystemCursor::SizeNWSE,
+            CursorIcon::Custom => return,
+        };
+
+        match Cursor::from_system(system_cursor) {
+            Ok(sdl_cursor) => {
+                sdl_cursor.set();
+                self.sdl_cursor = Some(sdl_cursor);
+                self.cursor = cursor;
+            }
+            Err(e) => log::error!("{}", e),
+        }
+    }
+
+    fn set_custom_cursor(
+        &mut self,
+        image: RgbaImage,
+        hot_x: u32,
+        hot_y: u32,
+    ) -> Result<(), String> {
+        let mut data = image.data;
+        let surface = sdl_surface(&mut data, image.width, image.height)?;
+        let sdl_cursor = Cursor::from_surface(surface, hot_x as _, hot_y as _)?;
+        sdl_cursor.set();
+        self.sdl_cursor = Some(sdl_cursor);
+        self.cursor = CursorIcon::Custom;
+        Ok(())
+    }
+
+    fn set_pointer_lock(&mut self, lock: bool) -> Result<(), String> {
+        self.sdl.mouse().set_relative_mouse_mode(lock);
+        Ok(())
+    }
+}
+
+fn sdl_surface(data: &mut [u8], width: u32, height: u32) -> Result<Surface, String> {
+    Surface::from_data(data, width, height, width * 4, PixelFormatEnum::RGBA32)
 }
 
 pub fn run<A, S, F, D>(mut app: A, mut state: S, mut update: F, mut draw: D) -> Result<(), String>
@@ -171,19 +333,36 @@ where
         .event_pump()
         .map_err(|e| e.to_string())?;
 
+    // gamepads are optional, the app can run without the controller subsystem
+    let controller_sys = app.system().window.sdl.game_controller().ok();
+    let mut controllers: HashMap<u32, GameController> = HashMap::new();
+
+    // sdl mouse events don't include the modifiers
+    let sdl_keyboard = app.system().window.sdl.keyboard();
+
     let mut running = true;
     let (mut last_mouse_x, mut last_mouse_y) = (0, 0);
+    let frame_time = app
+        .system()
+        .fps_target
+        .map(|fps| Duration::from_secs_f32(1.0 / fps.max(1) as f32));
+    let mut frame_start = Instant::now();
     while running {
         for evt in event_pump.poll_iter() {
             match evt {
                 SdlEvent::Quit { .. } => running = false,
                 SdlEvent::Window { win_event, .. } => match &win_event {
-                    WindowEvent::Resized(width, height) => {
+                    // SizeChanged is dispatched also when the size is changed by the app
+                    WindowEvent::SizeChanged(..) => {
                         //TODO Dpi?
-                        app.system().events.push(Event::WindowResize {
-                            width: *width,
-                            height: *height,
-                        });
+                        let (x, y, width, height) = app.system().window.drawable_area();
+                        app.system().draw.set_offset(x as _, y as _);
+                        app.system()
+                            .events
+                            .push(Event::WindowResize { width, height });
+                    }
+                    WindowEvent::Moved(x, y) => {
+                        app.system().events.push(Event::WindowMove { x: *x, y: *y });
                     }
                     WindowEvent::Leave => {
                         app.system().events.push(Event::MouseLeft {
@@ -202,29 +381,40 @@ where
                 SdlEvent::MouseButtonDown {
                     mouse_btn, x, y, ..
                 } => {
+                    let (x, y) = mouse_position(&app.system().window, x, y);
                     last_mouse_x = x;
                     last_mouse_y = y;
                     app.system().events.push(Event::MouseDown {
                         button: mouse_btn.to_nae(),
                         x,
                         y,
+                        modifiers: sdl_keyboard.mod_state().to_nae(),
                     });
                 }
                 SdlEvent::MouseButtonUp {
                     mouse_btn, x, y, ..
                 } => {
+                    let (x, y) = mouse_position(&app.system().window, x, y);
                     last_mouse_x = x;
                     last_mouse_y = y;
                     app.system().events.push(Event::MouseUp {
                         button: mouse_btn.to_nae(),
                         x,
                         y,
+                        modifiers: sdl_keyboard.mod_state().to_nae(),
                     });
                 }
-                SdlEvent::MouseMotion { x, y, .. } => {
+                SdlEvent::MouseMotion {
+                    x, y, xrel, yrel, ..
+                } => {
+                    let (x, y) = mouse_position(&app.system().window, x, y);
                     last_mouse_x = x;
                     last_mouse_y = y;
                     app.system().events.push(Event::MouseMove { x, y });
+                    app.system().events.push(Event::MouseMotion {
+                        delta_x: xrel as _,
+                        delta_y: yrel as _,
+                    });
                 }
                 SdlEvent::MouseWheel { x, y, .. } => {
                     let delta_x = x as f32 / 10.0;
@@ -234,16 +424,94 @@ where
                         .push(Event::MouseWheel { delta_x, delta_y })
                 }
                 SdlEvent::KeyDown {
-                    keycode, scancode, ..
+                    keycode,
+                    scancode,
+                    keymod,
+                    repeat,
+                    ..
                 } => {
                     let key = (keycode, scancode).to_nae();
-                    app.system().events.push(Event::KeyDown { key });
+                    app.system().events.push(Event::KeyDown {
+                        key,
+                        modifiers: keymod.to_nae(),
+                        is_repeat: repeat,
+                    });
                 }
                 SdlEvent::KeyUp {
-                    keycode, scancode, ..
+                    keycode,
+                    scancode,
+                    keymod,
+                    ..
                 } => {
                     let key = (keycode, scancode).to_nae();
-                    app.system().events.push(Event::KeyUp { key });
+                    app.system().events.push(Event::KeyUp {
+                        key,
+                        modifiers: keymod.to_nae(),
+                    });
+                }
+                SdlEvent::ControllerDeviceAdded { which, .. } => {
+                    let controller = controller_sys.as_ref().and_then(|c| c.open(which).ok());
+                    if let Some(controller) = controller {
+                        let id = controller.instance_id();
+                        controllers.insert(id, controller);
+                        app.system().events.push(Event::GamepadConnected { id });
+                    }
+                }
+                SdlEvent::ControllerDeviceRemoved { which, .. } => {
+                    if controllers.remove(&which).is_some() {
+                        app.system()
+                            .events
+                            .push(Event::GamepadDisconnected { id: which });
+                    }
+                }
+                SdlEvent::ControllerButtonDown { which, button, .. } => {
+                    app.system().events.push(Event::GamepadButtonDown {
+                        id: which,
+                        button: button.to_nae(),
+                    });
+                }
+                SdlEvent::ControllerButtonUp { which, button, .. } => {
+                    app.system().events.push(Event::GamepadButtonUp {
+                        id: which,
+                        button: button.to_nae(),
+                    });
+                }
+                SdlEvent::ControllerAxisMotion {
+                    which, axis, value, ..
+                } => {
+                    app.system().events.push(Event::GamepadAxisMotion {
+                        id: which,
+                        axis: axis.to_nae(),
+                        value: (value as f32 / i16::max_value() as f32).max(-1.0),
+                    });
+                }
+                SdlEvent::FingerDown {
+                    finger_id, x, y, ..
+                } => {
+                    let (x, y) = finger_position(&app.system().window, x, y);
+                    let id = finger_id as u64;
+                    app.system().events.push(Event::TouchStart { id, x, y });
+                }
+                SdlEvent::FingerMotion {
+                    finger_id, x, y, ..
+                } => {
+                    let (x, y) = finger_position(&app.system().window, x, y);
+                    let id = finger_id as u64;
+                    app.system().events.push(Event::TouchMove { id, x, y });
+                }
+                SdlEvent::FingerUp {
+                    finger_id, x, y, ..
+                } => {
+                    let (x, y) = finger_position(&app.system().window, x, y);
+                    let id = finger_id as u64;
+                    app.system().events.push(Event::TouchEnd { id, x, y });
+                }
+                // sdl doesn't report the files hovering the window
+                SdlEvent::DropFile { filename, .. } => {
+                    app.system().events.push(Event::FileDropped {
+                        path: filename,
+                        data: None,
+                    });
                 }
                 SdlEvent::TextInput { text, .. } => {
                     let mut chars = text.chars();
@@ -258,10 +526,56 @@ where
         update(&mut app, &mut state);
         draw(&mut app, &mut state);
         app.system().window.win.gl_swap_window();
+
+        if let Some(frame_time) = frame_time {
+            let elapsed = frame_start.elapsed();
+            if elapsed < frame_time {
+                thread::sleep(frame_time - elapsed);
+            }
+        }
+        frame_start = Instant::now();
     }
     Ok(())
 }
 
+// positions are relative to the drawable area when the aspect ratio is kept
+fn mouse_position(win: &Window, x: i32, y: i32) -> (i32, i32) {
+    let (offset_x, offset_y, _, _) = win.drawable_area();
+    (x - offset_x, y - offset_y)
+}
+
+// sdl gives the finger position normalized
+fn finger_position(win: &Window, x: f32, y: f32) -> (f32, f32) {
+    let (width, height) = win.win.size();
+    let (offset_x, offset_y, _, _) = win.drawable_area();
+    (
+        x * width as f32 - offset_x as f32,
+        y * height as f32 - offset_y as f32,
+    )
+}
+
+impl ToNaeValue for Mod {
+    type Kind = Modifiers;
+
+    fn to_nae(&self) -> Self::Kind {
+        let mut modifiers = Modifiers::empty();
+        modifiers.set(
+            Modifiers::SHIFT,
+            self.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD),
+        );
+        modifiers.set(
+            Modifiers::CTRL,
+            self.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD),
+        );
+        modifiers.set(Modifiers::ALT, self.intersects(Mod::LALTMOD | Mod::RALTMOD));
+        modifiers.set(
+            Modifiers::LOGO,
+            self.intersects(Mod::LGUIMOD | Mod::RGUIMOD),
+        );
+        modifiers
+    }
+}
+
 impl ToNaeValue for SdlMouseButton {
     type Kind = MouseButton;
 
@@ -277,6 +591,45 @@ impl ToNaeValue for SdlMouseButton {
     }
 }
 
+impl ToNaeValue for SdlButton {
+    type Kind = GamepadButton;
+
+    fn to_nae(&self) -> Self::Kind {
+        match &self {
+            SdlButton::A => GamepadButton::South,
+            SdlButton::B => GamepadButton::East,
+            SdlButton::X => GamepadButton::West,
+            SdlButton::Y => GamepadButton::North,
+            SdlButton::Back => GamepadButton::Select,
+            SdlButton::Guide => GamepadButton::Mode,
+            SdlButton::Start => GamepadButton::Start,
+            SdlButton::LeftStick => GamepadButton::LeftStick,
+            SdlButton::RightStick => GamepadButton::RightStick,
+            SdlButton::LeftShoulder => GamepadButton::LeftBumper,
+            SdlButton::RightShoulder => GamepadButton::RightBumper,
+            SdlButton::DPadUp => GamepadButton::DPadUp,
+            SdlButton::DPadDown => GamepadButton::DPadDown,
+            SdlButton::DPadLeft => GamepadButton::DPadLeft,
+            SdlButton::DPadRight => GamepadButton::DPadRight,
+        }
+    }
+}
+
+impl ToNaeValue for SdlAxis {
+    type Kind = GamepadAxis;
+
+    fn to_nae(&self) -> Self::Kind {
+        match &self {
+            SdlAxis::LeftX => GamepadAxis::LeftX,
+            SdlAxis::LeftY => GamepadAxis::LeftY,
+            SdlAxis::RightX => GamepadAxis::RightX,
+            SdlAxis::RightY => GamepadAxis::RightY,
+            SdlAxis::TriggerLeft => GamepadAxis::LeftTrigger,
+            SdlAxis::TriggerRight => GamepadAxis::RightTrigger,
+        }
+    }
+}
+
 /* TODO this sdl keycode to winit keycode are just tested on mac and maybe other platforms
     behave in some other way. Also, maybe some key could be missing, and jus tto think about
     it's better use sdl scancodes to winit virtual keycodes?
@@ -1,6 +1,10 @@
+use crate::common::letterbox;
 use nae_core::log;
-use nae_core::window::BaseWindow;
-use nae_core::{BaseApp, BaseSystem, BuilderOpts, Event, EventIterator, KeyCode, MouseButton};
+use nae_core::window::{BaseWindow, CursorIcon, RgbaImage};
+use nae_core::{
+    date_now, BaseApp, BaseSystem, BuilderOpts, Event, EventIterator, KeyCode, Modifiers,
+    MouseButton,
+};
 use std::cell::{RefCell, RefMut};
 use std::collections::VecDeque;
 use std::panic;
@@ -14,27 +18,54 @@ pub struct System {
     events: EventIterator,
     mouse_ctx: Option<MouseContext>,
     keyboard_ctx: Option<KeyboardContext>,
+    touch_ctx: Option<TouchContext>,
+    drop_ctx: Option<DropContext>,
+    clipboard: Rc<RefCell<Option<String>>>,
+    paste_cb: Closure<FnMut(web_sys::ClipboardEvent)>,
     draw: nae_gfx::Draw,
+    fps_target: Option<i32>,
 }
 
 impl BaseSystem for System {
     type Kind = Self;
     type Graphics = nae_gfx::Graphics;
     type Draw = nae_gfx::Draw;
+    type Window = Window;
 
     fn new(mut opts: BuilderOpts) -> Result<Self, String> {
         panic::set_hook(Box::new(console_error_panic_hook::hook));
         let win = Window::new(&opts)?;
         let draw = nae_gfx::Draw::new(&win.canvas)?;
+
+        // reading the clipboard is async on browsers so the text is stored when the user pastes
+        let clipboard = Rc::new(RefCell::new(None));
+        let clipboard_copy = clipboard.clone();
+        let paste_cb = window_add_event_listener("paste", move |e: web_sys::ClipboardEvent| {
+            if let Some(data) = e.clipboard_data() {
+                if let Ok(text) = data.get_data("text/plain") {
+                    *clipboard_copy.borrow_mut() = Some(text);
+                }
+            }
+        })?;
+
         Ok(Self {
             window: win,
             events: EventIterator::new(),
             mouse_ctx: None,
             keyboard_ctx: None,
+            touch_ctx: None,
+            drop_ctx: None,
+            clipboard,
+            paste_cb,
+            fps_target: opts.fps_target,
             draw,
         })
     }
 
+    fn window(&mut self) -> &mut Self::Window {
+        &mut self.window
+    }
+
     fn gfx(&mut self) -> &mut Self::Graphics {
         &mut self.draw.gfx
     }
@@ -66,6 +97,36 @@ impl BaseSystem for System {
     fn fullscreen(&self) -> bool {
         self.window.fullscreen()
     }
+
+    /// Returns the text set by the app or the last text pasted by the user
+    fn clipboard_text(&mut self) -> Option<String> {
+        self.clipboard.borrow().clone()
+    }
+
+    fn set_clipboard_text(&mut self, text: &str) -> Result<(), String> {
+        *self.clipboard.borrow_mut() = Some(text.to_string());
+        write_clipboard_text(text)
+    }
+}
+
+// navigator.clipboard is not available on web_sys without the unstable apis
+fn write_clipboard_text(text: &str) -> Result<(), String> {
+    let win = web_sys::window().ok_or("global window doesn't exists".to_string())?;
+    let clipboard = js_sys::Reflect::get(&win.navigator(), &JsValue::from_str("clipboard"))
+        .map_err(|e| format!("{:?}", e))?;
+    if clipboard.is_undefined() {
+        return Err("The clipboard API is not available.".to_string());
+    }
+
+    let write_text = js_sys::Reflect::get(&clipboard, &JsValue::from_str("writeText"))
+        .map_err(|e| format!("{:?}", e))?
+        .dyn_into::<js_sys::Function>()
+        .map_err(|e| format!("{:?}", e))?;
+
+    write_text
+        .call1(&clipboard, &JsValue::from_str(text))
+        .map_err(|e| format!("{:?}", e))?;
+    Ok(())
 }
 
 pub struct Window {
@@ -82,6 +143,11 @@ pub struct Window {
     request_fullscreen: Rc<RefCell<Option<bool>>>,
     fullscreen_last_size: Rc<RefCell<Option<(i32, i32)>>>,
     fullscreen_change_cb: Option<Closure<FnMut(web_sys::Event)>>,
+    cursor: CursorIcon,
+    cursor_visible: bool,
+    // the canvas doesn't dispatch any event when its size is changed by the app
+    pending_resize: Option<(i32, i32)>,
+    aspect: Option<f32>,
 }
 
 fn get_or_create_canvas(doc: &web_sys::Document) -> Result<HtmlCanvasElement, String> {
@@ -127,9 +193,15 @@ impl Window {
             .parent_element()
             .ok_or("Can't find the canvas parent element.")?;
 
+        let aspect = if opts.keep_aspect {
+            Some(opts.width as f32 / opts.height as f32)
+        } else {
+            None
+        };
+
         if opts.maximized {
-            let p_width = parent.client_width();
-            let p_height = parent.client_height();
+            let (p_width, p_height) =
+                fit_aspect(parent.client_width(), parent.client_height(), aspect);
             canvas.set_width(p_width as u32);
             canvas.set_height(p_height as u32);
         }
@@ -148,8 +220,51 @@ impl Window {
             request_fullscreen: Rc::new(RefCell::new(None)),
             fullscreen_last_size: Rc::new(RefCell::new(None)),
             fullscreen_change_cb: None,
+            cursor: CursorIcon::Default,
+            cursor_visible: true,
+            pending_resize: None,
+            aspect,
         })
     }
+
+    fn update_cursor_style(&mut self) {
+        let cursor = if self.cursor_visible {
+            css_cursor(self.cursor)
+        } else {
+            "none"
+        };
+
+        if let Err(e) = self.canvas.style().set_property("cursor", cursor) {
+            log::error!("{:?}", e);
+        }
+    }
+}
+
+// the canvas is constrained to the aspect ratio instead of letterboxed
+fn fit_aspect(width: i32, height: i32, aspect: Option<f32>) -> (i32, i32) {
+    match aspect {
+        Some(aspect) => {
+            let (_, _, width, height) = letterbox(width, height, aspect);
+            (width, height)
+        }
+        _ => (width, height),
+    }
+}
+
+fn css_cursor(cursor: CursorIcon) -> &'static str {
+    match cursor {
+        CursorIcon::Default | CursorIcon::Custom => "default",
+        CursorIcon::Crosshair => "crosshair",
+        CursorIcon::Hand => "pointer",
+        CursorIcon::Text => "text",
+        CursorIcon::Move => "move",
+        CursorIcon::Wait => "wait",
+        CursorIcon::NotAllowed => "not-allowed",
+        CursorIcon::ResizeHorizontal => "ew-resize",
+        CursorIcon::ResizeVertical => "ns-resize",
+        CursorIcon::ResizeNESW => "nesw-resize",
+        CursorIcon::ResizeNWSE => "nwse-resize",
+    }
 }
 
 impl BaseWindow for Window {
@@ -172,6 +287,74 @@ impl BaseWindow for Window {
     fn dpi(&self) -> f32 {
         1.0
     }
+
+    fn position(&self) -> (i32, i32) {
+        (0, 0)
+    }
+
+    fn cursor_visible(&self) -> bool {
+        self.cursor_visible
+    }
+
+    fn cursor(&self) -> CursorIcon {
+        self.cursor
+    }
+
+    fn pointer_locked(&self) -> bool {
+        let canvas: &Element = self.canvas.as_ref();
+        self.doc.pointer_lock_element().as_ref() == Some(canvas)
+    }
+
+    fn set_title(&mut self, title: &str) {
+        self.doc.set_title(title);
+        self.title = title.to_string();
+    }
+
+    fn set_size(&mut self, width: i32, height: i32) {
+        self.canvas.set_width(width as _);
+        self.canvas.set_height(height as _);
+        self.pending_resize = Some((width, height));
+    }
+
+    fn set_position(&mut self, _x: i32, _y: i32) {
+        log::warn!("Web target can't change the position of the canvas");
+    }
+
+    fn set_icon(&mut self, _icon: Option<RgbaImage>) -> Result<(), String> {
+        Err("Web target can't change the icon of the page.".to_string())
+    }
+
+    fn set_cursor_visible(&mut self, visible: bool) {
+        self.cursor_visible = visible;
+        self.update_cursor_style();
+    }
+
+    fn set_cursor(&mut self, cursor: CursorIcon) {
+        if cursor == CursorIcon::Custom {
+            return;
+        }
+
+        self.cursor = cursor;
+        self.update_cursor_style();
+    }
+
+    fn set_custom_cursor(
+        &mut self,
+        _image: RgbaImage,
+        _hot_x: u32,
+        _hot_y: u32,
+    ) -> Result<(), String> {
+        Err("Custom cursors are not supported on the web target.".to_string())
+    }
+
+    fn set_pointer_lock(&mut self, lock: bool) -> Result<(), String> {
+        if lock {
+            self.canvas.request_pointer_lock();
+        } else {
+            self.doc.exit_pointer_lock();
+        }
+        Ok(())
+    }
 }
 
 fn request_animation_frame(win: web_sys::Window, f: &Closure<dyn FnMut()>) {
@@ -209,7 +392,11 @@ fn enable_keyboard_events(
             let mut events = events_copy.borrow_mut();
 
             if let Some(k) = keyboard_code(&e.code()) {
-                let down_evt = Event::KeyDown { key: k };
+                let down_evt = Event::KeyDown {
+                    key: k,
+                    modifiers: keyboard_modifiers(&e),
+                    is_repeat: e.repeat(),
+                };
                 events.push_back(down_evt);
             }
 
@@ -233,7 +420,10 @@ fn enable_keyboard_events(
             let mut events = events_copy.borrow_mut();
 
             if let Some(k) = keyboard_code(&e.code()) {
-                let down_evt = Event::KeyUp { key: k };
+                let down_evt = Event::KeyUp {
+                    key: k,
+                    modifiers: keyboard_modifiers(&e),
+                };
                 events.push_back(down_evt);
             }
         },
@@ -272,12 +462,20 @@ fn enable_mouse_events(
 ) -> Result<(), String> {
     let events_copy = events.clone();
     let canvas_clone = canvas.clone();
-    ctx.move_cb = Some(canvas_add_event_listener(canvas, "mousemove", move |e| {
-        let (x, y) = canvas_position_from_global(&canvas_clone, e);
-        events_copy
-            .borrow_mut()
-            .push_back(Event::MouseMove { x, y });
-    })?);
+    ctx.move_cb = Some(canvas_add_event_listener(
+        canvas,
+        "mousemove",
+        move |e: web_sys::MouseEvent| {
+            let motion = Event::MouseMotion {
+                delta_x: e.movement_x() as _,
+                delta_y: e.movement_y() as _,
+            };
+            let (x, y) = canvas_position_from_global(&canvas_clone, e);
+            let mut events = events_copy.borrow_mut();
+            events.push_back(Event::MouseMove { x, y });
+            events.push_back(motion);
+        },
+    )?);
 
     let events_copy = events.clone();
     let canvas_clone = canvas.clone();
@@ -288,10 +486,14 @@ fn enable_mouse_events(
         move |e: web_sys::MouseEvent| {
             (*fullscreen_cb_copy.borrow())();
             let btn = mouse_button_to_nae(e.button());
+            let modifiers = mouse_modifiers(&e);
             let (x, y) = canvas_position_from_global(&canvas_clone, e);
-            events_copy
-                .borrow_mut()
-                .push_back(Event::MouseDown { button: btn, x, y });
+            events_copy.borrow_mut().push_back(Event::MouseDown {
+                button: btn,
+                x,
+                y,
+                modifiers,
+            });
         },
     )?);
 
@@ -303,10 +505,14 @@ fn enable_mouse_events(
         move |e: web_sys::MouseEvent| {
             (*fullscreen_cb_copy.borrow())();
             let btn = mouse_button_to_nae(e.button());
+            let modifiers = mouse_modifiers(&e);
             let (x, y) = canvas_position_from_global(&canvas_clone, e);
-            events_copy
-                .borrow_mut()
-                .push_back(Event::MouseUp { button: btn, x, y });
+            events_copy.borrow_mut().push_back(Event::MouseUp {
+                button: btn,
+                x,
+                y,
+                modifiers,
+            });
         },
     )?);
 
@@ -356,6 +562,226 @@ fn enable_mouse_events(
     Ok(())
 }
 
+struct TouchContext {
+    start_cb: Option<Closure<FnMut(web_sys::TouchEvent)>>,
+    move_cb: Option<Closure<FnMut(web_sys::TouchEvent)>>,
+    end_cb: Option<Closure<FnMut(web_sys::TouchEvent)>>,
+    cancel_cb: Option<Closure<FnMut(web_sys::TouchEvent)>>,
+}
+
+impl TouchContext {
+    fn new() -> Self {
+        Self {
+            start_cb: None,
+            move_cb: None,
+            end_cb: None,
+            cancel_cb: None,
+        }
+    }
+}
+
+fn enable_touch_events(
+    events: Rc<RefCell<VecDeque<Event>>>,
+    canvas: &HtmlCanvasElement,
+    ctx: &mut TouchContext,
+    fullscreen_cb: Rc<RefCell<Fn()>>,
+) -> Result<(), String> {
+    let events_copy = events.clone();
+    let canvas_clone = canvas.clone();
+    let fullscreen_cb_copy = fullscreen_cb.clone();
+    ctx.start_cb = Some(canvas_add_event_listener(
+        canvas,
+        "touchstart",
+        move |e: web_sys::TouchEvent| {
+            (*fullscreen_cb_copy.borrow())();
+            let mut events = events_copy.borrow_mut();
+            for (id, x, y) in changed_touches(&canvas_clone, &e) {
+                events.push_back(Event::TouchStart { id, x, y });
+            }
+        },
+    )?);
+
+    let events_copy = events.clone();
+    let canvas_clone = canvas.clone();
+    ctx.move_cb = Some(canvas_add_event_listener(
+        canvas,
+        "touchmove",
+        move |e: web_sys::TouchEvent| {
+            let mut events = events_copy.borrow_mut();
+            for (id, x, y) in changed_touches(&canvas_clone, &e) {
+                events.push_back(Event::TouchMove { id, x, y });
+            }
+        },
+    )?);
+
+    let events_copy = events.clone();
+    let canvas_clone = canvas.clone();
+    let fullscreen_cb_copy = fullscreen_cb.clone();
+    ctx.end_cb = Some(canvas_add_event_listener(
+        canvas,
+        "touchend",
+        move |e: web_sys::TouchEvent| {
+            (*fullscreen_cb_copy.borrow())();
+            let mut events = events_copy.borrow_mut();
+            for (id, x, y) in changed_touches(&canvas_clone, &e) {
+                events.push_back(Event::TouchEnd { id, x, y });
+            }
+        },
+    )?);
+
+    let events_copy = events.clone();
+    let canvas_clone = canvas.clone();
+    ctx.cancel_cb = Some(canvas_add_event_listener(
+        canvas,
+        "touchcancel",
+        move |e: web_sys::TouchEvent| {
+            let mut events = events_copy.borrow_mut();
+            for (id, x, y) in changed_touches(&canvas_clone, &e) {
+                events.push_back(Event::TouchCancel { id, x, y });
+            }
+        },
+    )?);
+
+    Ok(())
+}
+
+struct DropContext {
+    enter_cb: Option<Closure<FnMut(web_sys::DragEvent)>>,
+    over_cb: Option<Closure<FnMut(web_sys::DragEvent)>>,
+    leave_cb: Option<Closure<FnMut(web_sys::DragEvent)>>,
+    drop_cb: Option<Closure<FnMut(web_sys::DragEvent)>>,
+}
+
+impl DropContext {
+    fn new() -> Self {
+        Self {
+            enter_cb: None,
+            over_cb: None,
+            leave_cb: None,
+            drop_cb: None,
+        }
+    }
+}
+
+fn enable_drop_events(
+    events: Rc<RefCell<VecDeque<Event>>>,
+    canvas: &HtmlCanvasElement,
+    ctx: &mut DropContext,
+) -> Result<(), String> {
+    let events_copy = events.clone();
+    ctx.enter_cb = Some(canvas_add_event_listener(
+        canvas,
+        "dragenter",
+        move |e: web_sys::DragEvent| {
+            e.prevent_default();
+            events_copy
+                .borrow_mut()
+                .push_back(Event::FileHovered { path: None });
+        },
+    )?);
+
+    // the browser opens the file if the default action of dragover is not prevented
+    ctx.over_cb = Some(canvas_add_event_listener(
+        canvas,
+        "dragover",
+        |e: web_sys::DragEvent| {
+            e.prevent_default();
+        },
+    )?);
+
+    let events_copy = events.clone();
+    ctx.leave_cb = Some(canvas_add_event_listener(
+        canvas,
+        "dragleave",
+        move |_: web_sys::DragEvent| {
+            events_copy
+                .borrow_mut()
+                .push_back(Event::FileHoverCancelled);
+        },
+    )?);
+
+    let events_copy = events.clone();
+    ctx.drop_cb = Some(canvas_add_event_listener(
+        canvas,
+        "drop",
+        move |e: web_sys::DragEvent| {
+            e.prevent_default();
+            let files = match e.data_transfer().and_then(|data| data.files()) {
+                Some(files) => files,
+                _ => return,
+            };
+
+            for file in (0..files.length()).filter_map(|i| files.get(i)) {
+                if let Err(e) = read_dropped_file(events_copy.clone(), file) {
+                    log::error!("{}", e);
+                }
+            }
+        },
+    )?);
+
+    Ok(())
+}
+
+// the content is read async, the event is dispatched once it's loaded
+fn read_dropped_file(
+    events: Rc<RefCell<VecDeque<Event>>>,
+    file: web_sys::File,
+) -> Result<(), String> {
+    let reader = web_sys::FileReader::new().map_err(|e| format!("{:?}", e))?;
+    let reader_copy = reader.clone();
+    let path = file.name();
+    let onload = Closure::once_into_js(move || match reader_copy.result() {
+        Ok(buffer) => {
+            let data = js_sys::Uint8Array::new(&buffer).to_vec();
+            events.borrow_mut().push_back(Event::FileDropped {
+                path,
+                data: Some(data),
+            });
+        }
+        Err(e) => log::error!("{:?}", e),
+    });
+
+    reader.set_onload(Some(onload.unchecked_ref()));
+    reader
+        .read_as_array_buffer(&file)
+        .map_err(|e| format!("{:?}", e))
+}
+
+fn changed_touches(canvas: &HtmlCanvasElement, evt: &web_sys::TouchEvent) -> Vec<(u64, f32, f32)> {
+    let list = evt.changed_touches();
+    let rect = canvas.get_bounding_client_rect();
+    (0..list.length())
+        .filter_map(|i| list.get(i))
+        .map(|touch| {
+            let client_x = touch.client_x() as f64;
+            let client_y = touch.client_y() as f64;
+            let x =
+                (client_x - rect.left()) / (rect.right() - rect.left()) * (canvas.width() as f64);
+            let y =
+                (client_y - rect.top()) / (rect.bottom() - rect.top()) * (canvas.height() as f64);
+            (touch.identifier() as u64, x as f32, y as f32)
+        })
+        .collect()
+}
+
+fn keyboard_modifiers(e: &web_sys::KeyboardEvent) -> Modifiers {
+    let mut modifiers = Modifiers::empty();
+    modifiers.set(Modifiers::SHIFT, e.shift_key());
+    modifiers.set(Modifiers::CTRL, e.ctrl_key());
+    modifiers.set(Modifiers::ALT, e.alt_key());
+    modifiers.set(Modifiers::LOGO, e.meta_key());
+    modifiers
+}
+
+fn mouse_modifiers(e: &web_sys::MouseEvent) -> Modifiers {
+    let mut modifiers = Modifiers::empty();
+    modifiers.set(Modifiers::SHIFT, e.shift_key());
+    modifiers.set(Modifiers::CTRL, e.ctrl_key());
+    modifiers.set(Modifiers::ALT, e.alt_key());
+    modifiers.set(Modifiers::LOGO, e.meta_key());
+    modifiers
+}
+
 fn mouse_button_to_nae(btn: i16) -> MouseButton {
     match btn {
         0 => MouseButton::Left,
@@ -415,6 +841,17 @@ where
         fullscreen_cb.clone(),
     )?;
 
+    let mut touch_ctx = TouchContext::new();
+    enable_touch_events(
+        events.clone(),
+        &app.system().window.canvas,
+        &mut touch_ctx,
+        fullscreen_cb.clone(),
+    )?;
+
+    let mut drop_ctx = DropContext::new();
+    enable_drop_events(events.clone(), &app.system().window.canvas, &mut drop_ctx)?;
+
     if app.system().window.resizable {
         enable_resize_event(
             events.clone(),
@@ -428,6 +865,8 @@ where
     //Store the ref to the mouse context to avoid drop the closures, another option could be use forget but seems more clean.
     app.system().mouse_ctx = Some(mouse_ctx);
     app.system().keyboard_ctx = Some(keyboard_ctx);
+    app.system().touch_ctx = Some(touch_ctx);
+    app.system().drop_ctx = Some(drop_ctx);
 
     let callback = Rc::new(RefCell::new(move |app: &mut A, state: &mut S| {
         let mut frame_evts = events.borrow_mut();
@@ -435,13 +874,43 @@ where
             app.system().events.push(evt);
         }
 
+        if let Some((width, height)) = app.system().window.pending_resize.take() {
+            app.system()
+                .events
+                .push(Event::WindowResize { width, height });
+        }
+
         update(app, state);
         draw(app, state);
     }));
 
+    // targets above 1000fps run every frame, the timer only has millisecond precision
+    let frame_time = app
+        .system()
+        .fps_target
+        .map(|fps| (1000 / fps.max(1) as u64).max(1));
+    let mut last_frame = date_now();
+
     *cb_copy.borrow_mut() = Some(Closure::wrap(Box::new(move || {
-        let mut tick_handler = callback.borrow_mut();
-        (&mut *tick_handler)(&mut app, &mut state);
+        // raf runs at the screen refresh rate, skip frames to honor the fps target
+        let now = date_now();
+        let elapsed = now - last_frame;
+        let run_frame = match frame_time {
+            Some(frame_time) if elapsed < frame_time => false,
+            Some(frame_time) => {
+                last_frame = now - (elapsed % frame_time);
+                true
+            }
+            None => {
+                last_frame = now;
+                true
+            }
+        };
+
+        if run_frame {
+            let mut tick_handler = callback.borrow_mut();
+            (&mut *tick_handler)(&mut app, &mut state);
+        }
 
         //Web always run at max speed using raf (setTimeout has drawbacks)
         let win = web_sys::window().unwrap();
@@ -498,6 +967,7 @@ fn enable_resize_event(
     let parent = win.canvas_parent.clone();
     let min_size = win.min_size.clone();
     let max_size = win.max_size.clone();
+    let aspect = win.aspect;
     win.resize_cb = Some(window_add_event_listener(
         "resize",
         move |e: web_sys::Event| {
@@ -525,6 +995,7 @@ fn enable_resize_event(
                 }
             }
 
+            let (p_width, p_height) = fit_aspect(p_width, p_height, aspect);
             canvas.set_width(p_width as _);
             canvas.set_height(p_height as _);
             events.borrow_mut().push_back(Event::WindowResize {
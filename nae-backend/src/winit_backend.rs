@@ -1,43 +1,85 @@
-use crate::common::ToNaeValue;
-use glutin::{dpi::LogicalSize, ContextBuilder, PossiblyCurrent, WindowedContext};
-use nae_core::window::BaseWindow;
-use nae_core::{BaseApp, BaseSystem, BuilderOpts, Event, EventIterator, KeyCode, MouseButton};
+use crate::common::{letterbox, ToNaeValue};
+use copypasta::{ClipboardContext, ClipboardProvider};
+use gilrs::{Axis as GilrsAxis, Button as GilrsButton, EventType as GilrsEvent, Gilrs};
+use glutin::dpi::{LogicalPosition, LogicalSize};
+use glutin::{ContextBuilder, PossiblyCurrent, WindowedContext};
+use nae_core::log;
+use nae_core::window::{BaseWindow, CursorIcon, RgbaImage};
+use nae_core::{
+    BaseApp, BaseSystem, BuilderOpts, Event, EventIterator, GamepadAxis, GamepadButton, KeyCode,
+    Modifiers, MouseButton,
+};
 use std::cell::{Ref, RefCell, RefMut};
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 use std::rc::Rc;
 use std::sync::atomic::AtomicBool;
 use std::time::{Duration, Instant};
 use winit::event::MouseButton as WinitMB;
-use winit::event::{ElementState, Event as WinitEvent, VirtualKeyCode, WindowEvent};
+use winit::event::{
+    DeviceEvent, ElementState, Event as WinitEvent, ModifiersState, Touch, TouchPhase,
+    VirtualKeyCode, WindowEvent,
+};
 use winit::event_loop::{ControlFlow, EventLoop};
 use winit::monitor::MonitorHandle;
 use winit::window::Fullscreen::Borderless;
-use winit::window::WindowBuilder;
+use winit::window::{CursorIcon as WinitCursorIcon, Icon, WindowBuilder};
 
 pub struct System {
     window: Window,
     draw: nae_gfx::Draw,
     events: EventIterator,
     event_loop: Option<EventLoop<()>>,
+    fps_target: Option<i32>,
+    gilrs: Option<Gilrs>,
+    clipboard: Option<ClipboardContext>,
 }
 
 impl BaseSystem for System {
     type Kind = Self;
     type Graphics = nae_gfx::Graphics;
     type Draw = nae_gfx::Draw;
+    type Window = Window;
 
     fn new(mut opts: BuilderOpts) -> Result<Self, String> {
         let event_loop = EventLoop::new();
         let win = Window::new(&opts, &event_loop)?;
-        let draw = nae_gfx::Draw::new(&win.win)?;
+        let mut draw = nae_gfx::Draw::new(&win.win)?;
+        // the size and offset are scaled by the dpi so it must be set first
+        draw.update_dpi(win.dpi);
+        if win.aspect.is_some() {
+            let (x, y, width, height) = win.drawable_area();
+            draw.set_size(width as _, height as _);
+            draw.set_offset(x as _, y as _);
+        }
+
+        // winit doesn't support gamepads, the app can run without them if gilrs fails
+        let gilrs = Gilrs::new().ok();
+
+        // gilrs only dispatches the connections made after it starts
+        let mut events = EventIterator::new();
+        if let Some(gilrs) = &gilrs {
+            gilrs.gamepads().for_each(|(id, _)| {
+                events.push(Event::GamepadConnected {
+                    id: usize::from(id) as u32,
+                })
+            });
+        }
+
         Ok(Self {
             window: win,
             event_loop: Some(event_loop),
-            events: EventIterator::new(),
+            events,
+            fps_target: opts.fps_target,
+            gilrs,
+            clipboard: ClipboardContext::new().ok(),
             draw,
         })
     }
 
+    fn window(&mut self) -> &mut Self::Window {
+        &mut self.window
+    }
+
     fn gfx(&mut self) -> &mut Self::Graphics {
         &mut self.draw.gfx
     }
@@ -69,6 +111,57 @@ impl BaseSystem for System {
     fn fullscreen(&self) -> bool {
         self.window.fullscreen()
     }
+
+    fn clipboard_text(&mut self) -> Option<String> {
+        self.clipboard.as_mut()?.get_contents().ok()
+    }
+
+    fn set_clipboard_text(&mut self, text: &str) -> Result<(), String> {
+        self.clipboard
+            .as_mut()
+            .ok_or_else(|| "The clipboard is not available.".to_string())?
+            .set_contents(text.to_string())
+            .map_err(|e| e.to_string())
+    }
+
+    // the custom cursor is drawn over the frame at the mouse position
+    fn post_draw(&mut self) {
+        let Self { window, draw, .. } = self;
+        if !window.cursor_visible || window.pointer_locked {
+            return;
+        }
+
+        let (texture, hot_x, hot_y) = match &window.custom_cursor {
+            Some(CustomCursor {
+                texture: Some(texture),
+                hot_x,
+                hot_y,
+                ..
+            }) => (texture, *hot_x as f32, *hot_y as f32),
+            _ => return,
+        };
+
+        let (x, y) = match window.mouse_position {
+            Some(pos) => pos,
+            None => return,
+        };
+
+        let color = draw.color;
+        let alpha = draw.alpha;
+        draw.color = nae_core::Color::WHITE;
+        draw.alpha = 1.0;
+
+        draw.begin_without_clear();
+        draw.image(
+            texture,
+            x as f32 / window.dpi - hot_x,
+            y as f32 / window.dpi - hot_y,
+        );
+        draw.end();
+
+        draw.color = color;
+        draw.alpha = alpha;
+    }
 }
 
 pub struct Window {
@@ -76,6 +169,22 @@ pub struct Window {
     title: String,
     fullscreen: bool,
     dpi: f32,
+    cursor_visible: bool,
+    cursor: CursorIcon,
+    aspect: Option<f32>,
+    pointer_locked: bool,
+    custom_cursor: Option<CustomCursor>,
+    /// Physical position of the mouse on the drawable area, `None` if it's outside the window
+    mouse_position: Option<(i32, i32)>,
+}
+
+/// Image drawn over the app at the mouse position, winit can't use images as cursors
+struct CustomCursor {
+    /// Image waiting to be uploaded to a texture on the next draw
+    image: Option<RgbaImage>,
+    texture: Option<nae_gfx::Texture>,
+    hot_x: u32,
+    hot_y: u32,
 }
 
 impl Window {
@@ -94,8 +203,12 @@ impl Window {
             win_builder = win_builder.with_min_inner_size(LogicalSize::new(w, h));
         }
 
+        if let Some(icon) = &opts.icon {
+            win_builder = win_builder.with_window_icon(Some(winit_icon(icon.clone())?));
+        }
+
         let win_ctx = ContextBuilder::new()
-            .with_vsync(true)
+            .with_vsync(opts.fps_target.is_none())
             .with_gl(glutin::GlRequest::GlThenGles {
                 opengl_version: (3, 3),
                 opengles_version: (2, 0),
@@ -120,9 +233,42 @@ impl Window {
             fullscreen,
             win,
             dpi,
+            cursor_visible: true,
+            cursor: CursorIcon::Default,
+            pointer_locked: false,
+            custom_cursor: None,
+            mouse_position: None,
+            aspect: if opts.keep_aspect {
+                Some(opts.width as f32 / opts.height as f32)
+            } else {
+                None
+            },
         })
     }
 
+    /// Logical position and size of the area used to draw
+    fn drawable_area(&self) -> (i32, i32, i32, i32) {
+        let size = self.win.window().inner_size();
+        let width = (size.width as f32 / self.dpi) as i32;
+        let height = (size.height as f32 / self.dpi) as i32;
+        match self.aspect {
+            Some(aspect) => letterbox(width, height, aspect),
+            _ => (0, 0, width, height),
+        }
+    }
+
+    /// Physical offset of the drawable area, used to translate the mouse and touch positions
+    fn physical_offset(&self) -> (f64, f64) {
+        let (x, y, _, _) = self.drawable_area();
+        (x as f64 * self.dpi as f64, y as f64 * self.dpi as f64)
+    }
+
+    /// The OS cursor is hidden while the pointer is locked or a custom cursor is drawn
+    fn update_os_cursor(&self) {
+        let visible = self.cursor_visible && !self.pointer_locked && self.custom_cursor.is_none();
+        self.win.window().set_cursor_visible(visible);
+    }
+
     fn set_fullscreen(&mut self, full: bool) {
         self.fullscreen = full;
         if full {
@@ -136,11 +282,11 @@ impl Window {
 
 impl BaseWindow for Window {
     fn width(&self) -> i32 {
-        (self.win.window().inner_size().width as f32 / self.dpi) as _
+        self.drawable_area().2
     }
 
     fn height(&self) -> i32 {
-        (self.win.window().inner_size().height as f32 / self.dpi) as _
+        self.drawable_area().3
     }
 
     fn fullscreen(&self) -> bool {
@@ -154,6 +300,116 @@ impl BaseWindow for Window {
     fn dpi(&self) -> f32 {
         self.dpi
     }
+
+    fn position(&self) -> (i32, i32) {
+        match self.win.window().outer_position() {
+            Ok(pos) => (
+                (pos.x as f32 / self.dpi) as _,
+                (pos.y as f32 / self.dpi) as _,
+            ),
+            _ => (0, 0),
+        }
+    }
+
+    fn cursor_visible(&self) -> bool {
+        self.cursor_visible
+    }
+
+    fn cursor(&self) -> CursorIcon {
+        self.cursor
+    }
+
+    fn pointer_locked(&self) -> bool {
+        self.pointer_locked
+    }
+
+    fn set_title(&mut self, title: &str) {
+        self.win.window().set_title(title);
+        self.title = title.to_string();
+    }
+
+    fn set_size(&mut self, width: i32, height: i32) {
+        self.win
+            .window()
+            .set_inner_size(LogicalSize::new(width as f64, height as f64));
+    }
+
+    fn set_position(&mut self, x: i32, y: i32) {
+        self.win
+            .window()
+            .set_outer_position(LogicalPosition::new(x as f64, y as f64));
+    }
+
+    fn set_icon(&mut self, icon: Option<RgbaImage>) -> Result<(), String> {
+        let icon = match icon {
+            Some(icon) => Some(winit_icon(icon)?),
+            _ => None,
+        };
+        self.win.window().set_window_icon(icon);
+        Ok(())
+    }
+
+    fn set_cursor_visible(&mut self, visible: bool) {
+        self.cursor_visible = visible;
+        self.update_os_cursor();
+    }
+
+    fn set_cursor(&mut self, cursor: CursorIcon) {
+        if let Some(icon) = winit_cursor(cursor) {
+            self.win.window().set_cursor_icon(icon);
+            self.cursor = cursor;
+            self.custom_cursor = None;
+            self.update_os_cursor();
+        }
+    }
+
+    fn set_custom_cursor(
+        &mut self,
+        image: RgbaImage,
+        hot_x: u32,
+        hot_y: u32,
+    ) -> Result<(), String> {
+        self.custom_cursor = Some(CustomCursor {
+            image: Some(image),
+            texture: None,
+            hot_x,
+            hot_y,
+        });
+        self.cursor = CursorIcon::Custom;
+        self.update_os_cursor();
+        Ok(())
+    }
+
+    fn set_pointer_lock(&mut self, lock: bool) -> Result<(), String> {
+        self.win
+            .window()
+            .set_cursor_grab(lock)
+            .map_err(|e| e.to_string())?;
+        self.pointer_locked = lock;
+        self.update_os_cursor();
+        Ok(())
+    }
+}
+
+fn winit_icon(icon: RgbaImage) -> Result<Icon, String> {
+    Icon::from_rgba(icon.data, icon.width, icon.height).map_err(|e| e.to_string())
+}
+
+fn winit_cursor(cursor: CursorIcon) -> Option<WinitCursorIcon> {
+    Some(match cursor {
+        CursorIcon::Default => WinitCursorIcon::Default,
+        CursorIcon::Crosshair => WinitCursorIcon::Crosshair,
+        CursorIcon::Hand => WinitCursorIcon::Hand,
+        CursorIcon::Text => WinitCursorIcon::Text,
+        CursorIcon::Move => WinitCursorIcon::Move,
+        CursorIcon::Wait => WinitCursorIcon::Wait,
+        CursorIcon::NotAllowed => WinitCursorIcon::NotAllowed,
+        CursorIcon::ResizeHorizontal => WinitCursorIcon::EwResize,
+        CursorIcon::ResizeVertical => WinitCursorIcon::NsResize,
+        CursorIcon::ResizeNESW => WinitCursorIcon::NeswResize,
+        CursorIcon::ResizeNWSE => WinitCursorIcon::NwseResize,
+        CursorIcon::Custom => return None,
+    })
 }
 
 pub fn run<A, S, F, D>(mut app: A, mut state: S, mut update: F, mut draw: D) -> Result<(), String>
@@ -166,6 +422,14 @@ where
     let mut event_loop = app.system().event_loop.take().unwrap();
     let mut running = true;
     let (mut last_mouse_x, mut last_mouse_y) = (0, 0);
+    let fps = app.system().fps_target.unwrap_or(60).max(1);
+    let frame_time = Duration::from_secs_f32(1.0 / fps as f32);
+    let mut next_frame = Instant::now();
+    let mut modifiers = Modifiers::empty();
+    // winit doesn't tell if a key is repeated so the keys down are tracked here
+    let mut keys_down = HashSet::new();
+    // device events are received even if the window is not focused
+    let mut focused = true;
 
     event_loop.run(move |event, target, mut control| {
         if !running {
@@ -175,13 +439,17 @@ where
             WinitEvent::WindowEvent { ref event, .. } => match event {
                 WindowEvent::Resized(size) => {
                     app.system().window.win.resize(*size);
+                    let (x, y, width, height) = app.system().window.drawable_area();
+                    app.system().draw.set_offset(x as _, y as _);
+                    app.system()
+                        .events
+                        .push(Event::WindowResize { width, height });
+                }
+                WindowEvent::Moved(pos) => {
                     let dpi = app.system().window.dpi;
-                    let ww = (size.width as f32 / dpi) as _;
-                    let hh = (size.height as f32 / dpi) as _;
-
-                    app.system().events.push(Event::WindowResize {
-                        width: ww,
-                        height: hh,
+                    app.system().events.push(Event::WindowMove {
+                        x: (pos.x as f32 / dpi) as _,
+                        y: (pos.y as f32 / dpi) as _,
                     });
                 }
                 WindowEvent::CloseRequested => {
@@ -203,11 +471,13 @@ where
                             button: button.to_nae(),
                             x: last_mouse_x,
                             y: last_mouse_y,
+                            modifiers,
                         },
                         _ => Event::MouseUp {
                             button: button.to_nae(),
                             x: last_mouse_x,
                             y: last_mouse_y,
+                            modifiers,
                         },
                     };
                     app.system().events.push(evt);
@@ -238,20 +508,24 @@ where
                     app.system().events.push(evt);
                 }
                 WindowEvent::CursorMoved { position, .. } => {
-                    last_mouse_x = position.x as _;
-                    last_mouse_y = position.y as _;
+                    let (offset_x, offset_y) = app.system().window.physical_offset();
+                    last_mouse_x = (position.x - offset_x) as _;
+                    last_mouse_y = (position.y - offset_y) as _;
+                    app.system().window.mouse_position = Some((last_mouse_x, last_mouse_y));
                     app.system().events.push(Event::MouseMove {
                         x: last_mouse_x,
                         y: last_mouse_y,
                     });
                 }
                 WindowEvent::CursorEntered { .. } => {
+                    app.system().window.mouse_position = Some((last_mouse_x, last_mouse_y));
                     app.system().events.push(Event::MouseEnter {
                         x: last_mouse_x,
                         y: last_mouse_y,
                     });
                 }
                 WindowEvent::CursorLeft { .. } => {
+                    app.system().window.mouse_position = None;
                     app.system().events.push(Event::MouseLeft {
                         x: last_mouse_x,
                         y: last_mouse_y,
@@ -260,36 +534,234 @@ where
                 WindowEvent::KeyboardInput { input, .. } => {
                     let key = input.virtual_keycode.to_nae();
                     let evt = match input.state {
-                        ElementState::Pressed => Event::KeyDown { key },
-                        _ => Event::KeyUp { key },
+                        ElementState::Pressed => Event::KeyDown {
+                            key,
+                            modifiers,
+                            is_repeat: !keys_down.insert(key),
+                        },
+                        _ => {
+                            keys_down.remove(&key);
+                            Event::KeyUp { key, modifiers }
+                        }
                     };
                     app.system().events.push(evt);
                 }
+                WindowEvent::Touch(Touch {
+                    phase,
+                    location,
+                    id,
+                    ..
+                }) => {
+                    // same coordinates than the mouse events
+                    let (offset_x, offset_y) = app.system().window.physical_offset();
+                    let id = *id;
+                    let x = (location.x - offset_x) as f32;
+                    let y = (location.y - offset_y) as f32;
+                    let evt = match phase {
+                        TouchPhase::Started => Event::TouchStart { id, x, y },
+                        TouchPhase::Moved => Event::TouchMove { id, x, y },
+                        TouchPhase::Ended => Event::TouchEnd { id, x, y },
+                        TouchPhase::Cancelled => Event::TouchCancel { id, x, y },
+                    };
+                    app.system().events.push(evt);
+                }
+                WindowEvent::HoveredFile(path) => {
+                    app.system().events.push(Event::FileHovered {
+                        path: Some(path.to_string_lossy().to_string()),
+                    });
+                }
+                WindowEvent::DroppedFile(path) => {
+                    app.system().events.push(Event::FileDropped {
+                        path: path.to_string_lossy().to_string(),
+                        data: None,
+                    });
+                }
+                WindowEvent::HoveredFileCancelled => {
+                    app.system().events.push(Event::FileHoverCancelled);
+                }
+                WindowEvent::Focused(is_focused) => {
+                    focused = *is_focused;
+
+                    // the keys released while the window is unfocused are not dispatched
+                    if !focused {
+                        keys_down.clear();
+                    }
+                }
+                WindowEvent::ModifiersChanged(state) => {
+                    modifiers = state.to_nae();
+                }
                 WindowEvent::ReceivedCharacter(c) => {
                     app.system().events.push(Event::ReceivedCharacter(*c));
                 }
                 _ => {}
             },
+            WinitEvent::DeviceEvent {
+                event: DeviceEvent::MouseMotion { delta },
+                ..
+            } => {
+                if focused {
+                    app.system().events.push(Event::MouseMotion {
+                        delta_x: delta.0 as _,
+                        delta_y: delta.1 as _,
+                    });
+                }
+            }
             WinitEvent::MainEventsCleared => {
-                update(&mut app, &mut state);
-                app.system().window.win.window().request_redraw();
+                let now = Instant::now();
+                if now >= next_frame {
+                    next_frame += frame_time;
+                    if next_frame < now {
+                        next_frame = now + frame_time;
+                    }
+
+                    let sys = app.system();
+                    if let Some(gilrs) = &mut sys.gilrs {
+                        poll_gamepads(gilrs, &mut sys.events);
+                    }
+
+                    update(&mut app, &mut state);
+                    app.system().window.win.window().request_redraw();
+                }
             }
             WinitEvent::RedrawRequested(_) => {
+                upload_custom_cursor(&mut app);
                 draw(&mut app, &mut state);
                 app.system().window.win.swap_buffers();
             }
             _ => {}
         }
 
-        let mut time = Instant::now();
-        time = time + Duration::from_secs_f32(1.0 / 60.0);
-        *control = ControlFlow::WaitUntil(time);
-        //            *control = ControlFlow::Poll;
+        *control = ControlFlow::WaitUntil(next_frame);
     });
 
     Ok(())
 }
 
+/// Create the texture of the custom cursor set since the last frame
+fn upload_custom_cursor<A>(app: &mut A)
+where
+    A: BaseApp<System = System>,
+{
+    let image = app
+        .system()
+        .window
+        .custom_cursor
+        .as_mut()
+        .and_then(|cursor| cursor.image.take());
+
+    if let Some(image) = image {
+        match nae_gfx::Texture::from_rgba_image(app, &image) {
+            Ok(texture) => {
+                if let Some(cursor) = app.system().window.custom_cursor.as_mut() {
+                    cursor.texture = Some(texture);
+                }
+            }
+            Err(e) => log::error!("Custom cursor: {}", e),
+        }
+    }
+}
+
+fn poll_gamepads(gilrs: &mut Gilrs, events: &mut EventIterator) {
+    while let Some(gilrs::Event { id, event, .. }) = gilrs.next_event() {
+        let id = usize::from(id) as u32;
+        let evt = match event {
+            GilrsEvent::Connected => Event::GamepadConnected { id },
+            GilrsEvent::Disconnected => Event::GamepadDisconnected { id },
+            GilrsEvent::ButtonPressed(button, _) => Event::GamepadButtonDown {
+                id,
+                button: button.to_nae(),
+            },
+            GilrsEvent::ButtonReleased(button, _) => Event::GamepadButtonUp {
+                id,
+                button: button.to_nae(),
+            },
+            // gilrs reports the analog triggers as buttons
+            GilrsEvent::ButtonChanged(GilrsButton::LeftTrigger2, value, _) => {
+                Event::GamepadAxisMotion {
+                    id,
+                    axis: GamepadAxis::LeftTrigger,
+                    value,
+                }
+            }
+            GilrsEvent::ButtonChanged(GilrsButton::RightTrigger2, value, _) => {
+                Event::GamepadAxisMotion {
+                    id,
+                    axis: GamepadAxis::RightTrigger,
+                    value,
+                }
+            }
+            GilrsEvent::AxisChanged(axis, value, _) => {
+                let axis = axis.to_nae();
+                // gilrs uses positive Y up
+                let value = match axis {
+                    GamepadAxis::LeftY | GamepadAxis::RightY => -value,
+                    _ => value,
+                };
+                Event::GamepadAxisMotion { id, axis, value }
+            }
+            _ => continue,
+        };
+
+        events.push(evt);
+    }
+}
+
+impl ToNaeValue for GilrsButton {
+    type Kind = GamepadButton;
+
+    fn to_nae(&self) -> Self::Kind {
+        match self {
+            GilrsButton::South => GamepadButton::South,
+            GilrsButton::East => GamepadButton::East,
+            GilrsButton::West => GamepadButton::West,
+            GilrsButton::North => GamepadButton::North,
+            GilrsButton::LeftTrigger => GamepadButton::LeftBumper,
+            GilrsButton::RightTrigger => GamepadButton::RightBumper,
+            GilrsButton::LeftTrigger2 => GamepadButton::LeftTrigger,
+            GilrsButton::RightTrigger2 => GamepadButton::RightTrigger,
+            GilrsButton::Select => GamepadButton::Select,
+            GilrsButton::Start => GamepadButton::Start,
+            GilrsButton::Mode => GamepadButton::Mode,
+            GilrsButton::LeftThumb => GamepadButton::LeftStick,
+            GilrsButton::RightThumb => GamepadButton::RightStick,
+            GilrsButton::DPadUp => GamepadButton::DPadUp,
+            GilrsButton::DPadDown => GamepadButton::DPadDown,
+            GilrsButton::DPadLeft => GamepadButton::DPadLeft,
+            GilrsButton::DPadRight => GamepadButton::DPadRight,
+            _ => GamepadButton::Unknown,
+        }
+    }
+}
+
+impl ToNaeValue for GilrsAxis {
+    type Kind = GamepadAxis;
+
+    fn to_nae(&self) -> Self::Kind {
+        match self {
+            GilrsAxis::LeftStickX => GamepadAxis::LeftX,
+            GilrsAxis::LeftStickY => GamepadAxis::LeftY,
+            GilrsAxis::RightStickX => GamepadAxis::RightX,
+            GilrsAxis::RightStickY => GamepadAxis::RightY,
+            GilrsAxis::LeftZ => GamepadAxis::LeftTrigger,
+            GilrsAxis::RightZ => GamepadAxis::RightTrigger,
+            _ => GamepadAxis::Unknown,
+        }
+    }
+}
+
+impl ToNaeValue for ModifiersState {
+    type Kind = Modifiers;
+
+    fn to_nae(&self) -> Self::Kind {
+        let mut modifiers = Modifiers::empty();
+        modifiers.set(Modifiers::SHIFT, self.shift());
+        modifiers.set(Modifiers::CTRL, self.ctrl());
+        modifiers.set(Modifiers::ALT, self.alt());
+        modifiers.set(Modifiers::LOGO, self.logo());
+        modifiers
+    }
+}
+
 impl ToNaeValue for WinitMB {
     type Kind = MouseButton;
 
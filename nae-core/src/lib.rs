@@ -16,6 +16,7 @@ pub use resources::*;
 
 pub use rand;
 pub use rand_pcg;
+use serde::{Deserialize, Serialize};
 use std::cell::{Ref, RefCell, RefMut};
 use std::collections::VecDeque;
 use std::rc::Rc;
@@ -31,6 +32,9 @@ pub struct BuilderOpts {
     pub max_size: Option<(i32, i32)>,
     pub maximized: bool,
     pub resizable: bool,
+    pub icon: Option<window::RgbaImage>,
+    /// Keep the aspect ratio of the initial size when the window is resized
+    pub keep_aspect: bool,
 }
 
 impl Default for BuilderOpts {
@@ -45,6 +49,8 @@ impl Default for BuilderOpts {
             max_size: None,
             maximized: false,
             resizable: false,
+            icon: None,
+            keep_aspect: false,
         }
     }
 }
@@ -58,8 +64,10 @@ pub trait BaseSystem {
     type Kind: BaseSystem;
     type Graphics: BaseGfx;
     type Draw;
+    type Window: window::BaseWindow;
 
     fn new(opts: BuilderOpts) -> Result<Self::Kind, String>;
+    fn window(&mut self) -> &mut Self::Window;
     fn gfx(&mut self) -> &mut Self::Graphics;
     fn draw(&mut self) -> &mut Self::Draw;
     fn events(&mut self) -> &mut EventIterator;
@@ -68,6 +76,12 @@ pub trait BaseSystem {
     fn dpi(&self) -> f32;
     fn set_fullscreen(&mut self, full: bool);
     fn fullscreen(&self) -> bool;
+    fn clipboard_text(&mut self) -> Option<String>;
+    fn set_clipboard_text(&mut self, text: &str) -> Result<(), String>;
+
+    /// Called after the app draws a frame and before its graphics stats are read,
+    /// the backends can use it to draw over the app
+    fn post_draw(&mut self) {}
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -75,6 +89,14 @@ pub fn date_now() -> u64 {
     js_sys::Date::now() as u64
 }
 
+/// Milliseconds with sub-millisecond precision, used to measure elapsed time
+#[cfg(target_arch = "wasm32")]
+pub fn performance_now() -> f64 {
+    web_sys::window()
+        .and_then(|win| win.performance())
+        .map_or_else(js_sys::Date::now, |perf| perf.now())
+}
+
 #[derive(Debug, Clone)]
 pub struct EventIterator(VecDeque<Event>);
 
@@ -94,6 +116,11 @@ impl EventIterator {
     pub fn take_events(&mut self) -> EventIterator {
         EventIterator(std::mem::replace(&mut self.0, VecDeque::new()))
     }
+
+    /// Iterate over the pending events without consuming them
+    pub fn iter(&self) -> std::collections::vec_deque::Iter<Event> {
+        self.0.iter()
+    }
 }
 
 impl Iterator for EventIterator {
@@ -113,7 +140,19 @@ pub fn date_now() -> u64 {
         .as_millis() as u64
 }
 
-#[derive(Debug, PartialEq, PartialOrd, Clone)]
+#[cfg(not(target_arch = "wasm32"))]
+lazy_static::lazy_static! {
+    static ref START_TIME: std::time::Instant = std::time::Instant::now();
+}
+
+/// Milliseconds with sub-millisecond precision, used to measure elapsed time.
+/// The time is monotonic and starts with the first call
+#[cfg(not(target_arch = "wasm32"))]
+pub fn performance_now() -> f64 {
+    START_TIME.elapsed().as_secs_f64() * 1000.0
+}
+
+#[derive(Debug, PartialEq, PartialOrd, Clone, Serialize, Deserialize)]
 /// Input events made by the user
 pub enum Event {
     /// Dispatched when the window is about to close
@@ -136,15 +175,28 @@ pub enum Event {
     /// `mouse` feature must be enabled
     MouseMove { x: i32, y: i32 },
 
+    /// Relative motion of the mouse, it's dispatched also when the pointer is locked
+    MouseMotion { delta_x: f32, delta_y: f32 },
+
     //#[cfg(feature = "mouse")]
     /// A mouse button is down on this position
     /// `mouse` feature must be enabled
-    MouseDown { button: MouseButton, x: i32, y: i32 },
+    MouseDown {
+        button: MouseButton,
+        x: i32,
+        y: i32,
+        modifiers: Modifiers,
+    },
 
     //#[cfg(feature = "mouse")]
     /// A mouse button was released on this position
     /// `mouse` feature must be enabled
-    MouseUp { button: MouseButton, x: i32, y: i32 },
+    MouseUp {
+        button: MouseButton,
+        x: i32,
+        y: i32,
+        modifiers: Modifiers,
+    },
 
     //#[cfg(feature = "mouse")]
     /// Mouse wheel was moved and this are his delta
@@ -157,18 +209,74 @@ pub enum Event {
     /// Mouse cursor has left the window's app
     MouseLeft { x: i32, y: i32 },
 
-    /// Keyboard's key down
-    KeyDown { key: KeyCode },
+    /// A finger touched the screen, the id is unique while the touch lasts
+    TouchStart { id: u64, x: f32, y: f32 },
+
+    /// A touch changed its position
+    TouchMove { id: u64, x: f32, y: f32 },
+
+    /// A finger was lifted from the screen
+    TouchEnd { id: u64, x: f32, y: f32 },
+
+    /// The system cancelled the touch (e.g. too many fingers)
+    TouchCancel { id: u64, x: f32, y: f32 },
+
+    /// Keyboard's key down, `is_repeat` is true when it's generated by the OS auto-repeat
+    KeyDown {
+        key: KeyCode,
+        modifiers: Modifiers,
+        is_repeat: bool,
+    },
 
     /// Keyboard's key up
-    KeyUp { key: KeyCode },
+    KeyUp { key: KeyCode, modifiers: Modifiers },
 
     /// Unicode char pressed
     ReceivedCharacter(char),
+
+    /// A file is dragged over the window, the path is unknown on the web target
+    FileHovered { path: Option<String> },
+
+    /// A file was dropped on the window. The web target only knows the file's name,
+    /// its content is read and sent on `data`
+    FileDropped { path: String, data: Option<Vec<u8>> },
+
+    /// The file dragged over the window left without being dropped
+    FileHoverCancelled,
+
+    /// A gamepad was connected, the id is unique while the gamepad stays connected
+    GamepadConnected { id: u32 },
+
+    /// A gamepad was disconnected
+    GamepadDisconnected { id: u32 },
+
+    /// Gamepad's button down
+    GamepadButtonDown { id: u32, button: GamepadButton },
+
+    /// Gamepad's button up
+    GamepadButtonUp { id: u32, button: GamepadButton },
+
+    /// Gamepad's axis moved, sticks go from -1.0 to 1.0 (positive Y is down)
+    /// and triggers from 0.0 to 1.0
+    GamepadAxisMotion {
+        id: u32,
+        axis: GamepadAxis,
+        value: f32,
+    },
+
+    /// A resource failed to load or parse, the resource's state will be `ResourceState::Failed`.
+    /// If it fails while reloading the resource keeps the previous data
+    ResourceFailed { file: String, error: String },
+
+    /// The file of a resource changed on the disk and the resource was updated with it
+    ResourceReloaded { file: String },
+
+    /// All the resources of the group finished loading, some of them could have failed
+    ResourceGroupReady { group: String },
 }
 
 //#[cfg(feature = "mouse")]
-#[derive(Clone, Copy, Hash, Debug, Eq, PartialEq, Ord, PartialOrd)]
+#[derive(Clone, Copy, Hash, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
 /// Represents a button of a mouse
 pub enum MouseButton {
     Left,
@@ -177,9 +285,106 @@ pub enum MouseButton {
     Other(u8),
 }
 
+/// Modifier keys (shift, ctrl, alt and logo) down when an event was dispatched
+#[derive(
+    Clone, Copy, Hash, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize,
+)]
+pub struct Modifiers(u8);
+
+impl Modifiers {
+    pub const SHIFT: Modifiers = Modifiers(1);
+    pub const CTRL: Modifiers = Modifiers(1 << 1);
+    pub const ALT: Modifiers = Modifiers(1 << 2);
+    /// Windows key on windows, command key on mac
+    pub const LOGO: Modifiers = Modifiers(1 << 3);
+
+    pub fn empty() -> Self {
+        Modifiers(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn contains(&self, other: Modifiers) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn set(&mut self, other: Modifiers, value: bool) {
+        if value {
+            self.0 |= other.0;
+        } else {
+            self.0 &= !other.0;
+        }
+    }
+
+    pub fn shift(&self) -> bool {
+        self.contains(Modifiers::SHIFT)
+    }
+
+    pub fn ctrl(&self) -> bool {
+        self.contains(Modifiers::CTRL)
+    }
+
+    pub fn alt(&self) -> bool {
+        self.contains(Modifiers::ALT)
+    }
+
+    pub fn logo(&self) -> bool {
+        self.contains(Modifiers::LOGO)
+    }
+}
+
+impl std::ops::BitOr for Modifiers {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Modifiers(self.0 | rhs.0)
+    }
+}
+
+#[derive(Clone, Copy, Hash, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+/// Represents a button of a gamepad using the xbox layout positions
+pub enum GamepadButton {
+    /// Bottom face button (A on xbox)
+    South,
+    /// Right face button (B on xbox)
+    East,
+    /// Left face button (X on xbox)
+    West,
+    /// Top face button (Y on xbox)
+    North,
+    LeftBumper,
+    RightBumper,
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+    Mode,
+    LeftStick,
+    RightStick,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+    Unknown,
+}
+
+#[derive(Clone, Copy, Hash, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+/// Represents an analog axis of a gamepad
+pub enum GamepadAxis {
+    LeftX,
+    LeftY,
+    RightX,
+    RightY,
+    LeftTrigger,
+    RightTrigger,
+    Unknown,
+}
+
 /// Represent a key
 /// Enum from winit but added an Unknown key.
-#[derive(Clone, Copy, Hash, Debug, Eq, PartialEq, Ord, PartialOrd)]
+#[derive(Clone, Copy, Hash, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
 #[repr(u32)]
 pub enum KeyCode {
     /// The '1' key over the letters.
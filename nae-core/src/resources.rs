@@ -28,6 +28,14 @@ pub enum VerticalAlign {
     Bottom,
 }
 
+/// Loading state of a resource
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResourceState {
+    Pending,
+    Loaded,
+    Failed(String),
+}
+
 /// Represents an external resource
 pub trait Resource<T>: Clone {
     /// Create a empty resource ready to be loaded
@@ -35,4 +43,7 @@ pub trait Resource<T>: Clone {
 
     /// Parse byte data to create to fill the resource
     fn set_data(&mut self, app: &mut T, data: Vec<u8>) -> Result<(), String>;
+
+    /// Called when the file can't be loaded or parsed, by default the error is ignored
+    fn set_error(&mut self, _err: String) {}
 }
@@ -4,4 +4,67 @@ pub trait BaseWindow {
     fn fullscreen(&self) -> bool;
     fn title(&self) -> &str;
     fn dpi(&self) -> f32;
+    fn position(&self) -> (i32, i32);
+    fn cursor_visible(&self) -> bool;
+    fn cursor(&self) -> CursorIcon;
+    fn pointer_locked(&self) -> bool;
+    fn set_title(&mut self, title: &str);
+    fn set_size(&mut self, width: i32, height: i32);
+    fn set_position(&mut self, x: i32, y: i32);
+    fn set_icon(&mut self, icon: Option<RgbaImage>) -> Result<(), String>;
+    fn set_cursor_visible(&mut self, visible: bool);
+    fn set_cursor(&mut self, cursor: CursorIcon);
+    fn set_custom_cursor(&mut self, image: RgbaImage, hot_x: u32, hot_y: u32)
+        -> Result<(), String>;
+    fn set_pointer_lock(&mut self, lock: bool) -> Result<(), String>;
+}
+
+/// Pixels in RGBA format used for the window's icon and the cursor
+#[derive(Debug, Clone, PartialEq)]
+pub struct RgbaImage {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+impl RgbaImage {
+    pub fn new(width: u32, height: u32, data: Vec<u8>) -> Result<Self, String> {
+        let len = (width as usize)
+            .checked_mul(height as usize)
+            .and_then(|n| n.checked_mul(4))
+            .ok_or_else(|| format!("The image of {}x{} is too big", width, height))?;
+
+        if data.len() != len {
+            return Err(format!(
+                "Invalid RGBA data length {} for an image of {}x{}",
+                data.len(),
+                width,
+                height
+            ));
+        }
+
+        Ok(Self {
+            width,
+            height,
+            data,
+        })
+    }
+}
+
+/// Built-in mouse cursor icons
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CursorIcon {
+    Default,
+    Crosshair,
+    Hand,
+    Text,
+    Move,
+    Wait,
+    NotAllowed,
+    ResizeHorizontal,
+    ResizeVertical,
+    ResizeNESW,
+    ResizeNWSE,
+    /// A custom image is used, it can't be set using `set_cursor`
+    Custom,
 }
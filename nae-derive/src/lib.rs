@@ -1,7 +1,11 @@
 extern crate proc_macro;
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::ItemFn;
+use syn::spanned::Spanned;
+use syn::{
+    Data, DeriveInput, Error, Fields, GenericArgument, ItemFn, Lit, Meta, NestedMeta,
+    PathArguments, Type,
+};
 
 #[proc_macro_attribute]
 pub fn main(_attr: TokenStream, item: TokenStream) -> TokenStream {
@@ -22,3 +26,163 @@ fn handle_func(input: ItemFn) -> TokenStream {
 
     expand.into()
 }
+
+/// Implement `Resource` and `ResourceParser` for a struct with a single `ResourceCell<T>` field.
+/// The value is created with `Self::parse(app, data) -> Result<T, String>`, or with the function
+/// set using `#[resource(parse = "path::to::fn")]`
+#[proc_macro_derive(Resource, attributes(resource))]
+pub fn derive_resource(item: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(item as DeriveInput);
+    match handle_resource(input) {
+        Ok(expand) => expand.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn handle_resource(input: DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let parse = parse_fn(&input)?;
+
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(Error::new(
+                input.span(),
+                "Resource can only be derived for structs",
+            ))
+        }
+    };
+
+    if fields.iter().count() != 1 {
+        return Err(Error::new(
+            fields.span(),
+            "The struct must have only one field of type ResourceCell<T>",
+        ));
+    }
+
+    let field = fields.iter().next().unwrap();
+    let value_type = cell_value_type(&field.ty)?;
+    let (member, prepare) = match fields {
+        Fields::Named(_) => {
+            let name = field.ident.clone().unwrap();
+            (
+                quote!(#name),
+                quote!(Self { #name: ::nae::prelude::ResourceCell::new() }),
+            )
+        }
+        _ => {
+            let index = syn::Index::from(0);
+            (
+                quote!(#index),
+                quote!(Self(::nae::prelude::ResourceCell::new())),
+            )
+        }
+    };
+
+    let expand = quote! {
+        impl #impl_generics ::nae::prelude::Resource<::nae::App> for #ident #ty_generics #where_clause {
+            fn prepare(_app: &mut ::nae::App, _file: &str) -> Result<Self, String> {
+                Ok(#prepare)
+            }
+
+            fn set_data(&mut self, app: &mut ::nae::App, data: Vec<u8>) -> Result<(), String> {
+                let value: #value_type = #parse(app, data)?;
+                self.#member.set(value);
+                Ok(())
+            }
+
+            fn set_error(&mut self, err: String) {
+                self.#member.set_error(err);
+            }
+        }
+
+        impl #impl_generics ::nae::prelude::ResourceParser for #ident #ty_generics #where_clause {
+            type App = ::nae::App;
+
+            fn parse_resource(&mut self, app: &mut ::nae::App, data: Vec<u8>) -> Result<(), String> {
+                <Self as ::nae::prelude::Resource<::nae::App>>::set_data(self, app, data)
+            }
+
+            fn set_error(&mut self, err: String) {
+                <Self as ::nae::prelude::Resource<::nae::App>>::set_error(self, err)
+            }
+        }
+
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// Return a reference to the value, `None` if it's not loaded yet
+            pub fn data(&self) -> Option<::std::cell::Ref<#value_type>> {
+                self.#member.data()
+            }
+
+            /// Return a mutable reference to the value, `None` if it's not loaded yet
+            pub fn data_mut(&mut self) -> Option<::std::cell::RefMut<#value_type>> {
+                self.#member.data_mut()
+            }
+
+            /// Returns if the resource is already loaded
+            pub fn is_loaded(&self) -> bool {
+                self.#member.is_loaded()
+            }
+
+            /// Returns the loading state of the resource
+            pub fn state(&self) -> ::nae::prelude::ResourceState {
+                self.#member.state()
+            }
+        }
+    };
+
+    Ok(expand)
+}
+
+/// Returns the function set with `#[resource(parse = "...")]` or `Self::parse`
+fn parse_fn(input: &DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let mut parse = None;
+    for attr in &input.attrs {
+        let meta = match attr.parse_meta() {
+            Ok(Meta::List(list)) if list.ident == "resource" => list,
+            _ => continue,
+        };
+
+        for nested in meta.nested.iter() {
+            let path = match nested {
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.ident == "parse" => match &nv.lit {
+                    Lit::Str(path) => path.parse::<syn::Path>()?,
+                    lit => return Err(Error::new(lit.span(), "Expected a path as a string")),
+                },
+                _ => return Err(Error::new(nested.span(), "Expected `parse = \"...\"`")),
+            };
+
+            if parse.is_some() {
+                return Err(Error::new(
+                    nested.span(),
+                    "The parse function is already set",
+                ));
+            }
+            parse = Some(quote!(#path));
+        }
+    }
+
+    Ok(parse.unwrap_or_else(|| quote!(Self::parse)))
+}
+
+/// Returns the `T` of a `ResourceCell<T>`
+fn cell_value_type(ty: &Type) -> Result<&Type, Error> {
+    let err = || Error::new(ty.span(), "The field must be of type ResourceCell<T>");
+    let segment = match ty {
+        Type::Path(path) => path.path.segments.iter().last().ok_or_else(err)?,
+        _ => return Err(err()),
+    };
+
+    if segment.ident != "ResourceCell" {
+        return Err(err());
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => match args.args.iter().next() {
+            Some(GenericArgument::Type(ty)) if args.args.len() == 1 => Ok(ty),
+            _ => Err(err()),
+        },
+        _ => Err(err()),
+    }
+}
@@ -317,6 +317,7 @@ impl TextBatcher {
         gfx.bind_vertex_buffer(&self.vbo, &self.vertices);
         gfx.bind_index_buffer(&self.ibo, &self.indices);
         gfx.draw(0, self.index as _);
+        gfx.stats.text_flushes += 1;
 
         self.index = 0;
     }
@@ -553,6 +554,7 @@ impl BaseBatcher for PatternBatcher {
             gfx.bind_vertex_buffer(&self.vbo, &self.vertices);
             gfx.bind_index_buffer(&self.ibo, &self.indices);
             gfx.draw(0, self.index as _);
+            gfx.stats.pattern_flushes += 1;
         }
 
         self.index = 0;
@@ -766,6 +768,7 @@ impl BaseBatcher for ImageBatcher {
             gfx.bind_vertex_buffer(&self.vbo, &self.vertices);
             gfx.bind_index_buffer(&self.ibo, &self.indices);
             gfx.draw(0, self.index as _);
+            gfx.stats.image_flushes += 1;
         }
 
         self.index = 0;
@@ -1006,6 +1009,7 @@ impl BaseBatcher for ColorBatcher {
         gfx.bind_vertex_buffer(&self.vbo, &self.vertices);
         gfx.bind_index_buffer(&self.ibo, &self.indices);
         gfx.draw(0, self.index as i32);
+        gfx.stats.color_flushes += 1;
         self.index = 0;
     }
 
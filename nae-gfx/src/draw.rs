@@ -104,6 +104,11 @@ impl Draw {
             projection(width, height, self.gfx.render_target.is_some(), self.dpi);
     }
 
+    /// Set the position of the drawable area on the window, used to letterbox the drawable
+    pub fn set_offset(&mut self, x: f32, y: f32) {
+        self.gfx.set_offset(x * self.dpi, y * self.dpi);
+    }
+
     pub fn set_text_align(&mut self, horizontal: HorizontalAlign, vertical: VerticalAlign) {
         self.text_horizontal_align = horizontal;
         self.text_vertical_align = vertical;
@@ -187,15 +192,14 @@ impl Draw {
     }
 
     pub fn begin(&mut self, color: Color) {
-        let projection = match &self.gfx.render_target {
-            Some(rt) => projection(rt.width(), rt.height(), true, 1.0),
-            None => projection(self.gfx.width, self.gfx.height, false, self.dpi),
-        };
-
-        self.render_projection = projection;
-
         self.clear_options.color = Some(color);
-        self.gfx.begin(&self.clear_options);
+        begin_pass(self);
+    }
+
+    /// Begin a new pass keeping what was drawn on the previous passes
+    pub fn begin_without_clear(&mut self) {
+        self.clear_options.color = None;
+        begin_pass(self);
     }
 
     pub fn end(&mut self) {
@@ -674,6 +678,16 @@ fn clear_mask(draw: &mut Draw) {
     batcher.clear_mask(&mut draw.gfx, &draw.mask, Color::TRANSPARENT);
 }
 
+fn begin_pass(draw: &mut Draw) {
+    let projection = match &draw.gfx.render_target {
+        Some(rt) => projection(rt.width(), rt.height(), true, 1.0),
+        None => projection(draw.gfx.width, draw.gfx.height, false, draw.dpi),
+    };
+
+    draw.render_projection = projection;
+    draw.gfx.begin(&draw.clear_options);
+}
+
 fn flush(draw: &mut Draw) {
     let mut batcher: &mut BaseBatcher = match draw.last_paint_mode {
         PaintMode::Color => &mut draw.color_batcher,
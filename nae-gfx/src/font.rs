@@ -7,7 +7,8 @@ use glyph_brush::{
     Section,
 };
 use nae_core::{
-    BaseApp, BaseSystem, HorizontalAlign, Resource, TextureFilter, TextureFormat, VerticalAlign,
+    BaseApp, BaseSystem, HorizontalAlign, Resource, ResourceState, TextureFilter, TextureFormat,
+    VerticalAlign,
 };
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -35,7 +36,10 @@ impl Font {
         S: BaseSystem<Graphics = Graphics, Draw = Draw>,
     {
         let mut font = Font {
-            inner: Rc::new(RefCell::new(InnerFont { id: None })),
+            inner: Rc::new(RefCell::new(InnerFont {
+                id: None,
+                error: None,
+            })),
         };
 
         font.set_data(app, data.to_vec());
@@ -60,6 +64,16 @@ impl Font {
     pub fn is_loaded(&self) -> bool {
         self.inner.borrow().id.is_some()
     }
+
+    /// Returns the loading state of the resource
+    pub fn state(&self) -> ResourceState {
+        let inner = self.inner.borrow();
+        match (&inner.id, &inner.error) {
+            (Some(_), _) => ResourceState::Loaded,
+            (_, Some(err)) => ResourceState::Failed(err.clone()),
+            _ => ResourceState::Pending,
+        }
+    }
 }
 
 impl<T, S> Resource<T> for Font
@@ -69,7 +83,10 @@ where
 {
     fn prepare(app: &mut T, _file: &str) -> Result<Self, String> {
         Ok(Font {
-            inner: Rc::new(RefCell::new(InnerFont { id: None })),
+            inner: Rc::new(RefCell::new(InnerFont {
+                id: None,
+                error: None,
+            })),
         })
     }
 
@@ -77,10 +94,15 @@ where
         let id = add_font(app.system().draw(), data);
         *self.inner.borrow_mut() = InnerFont {
             id: Some(FontId(id)),
+            error: None,
         };
 
         Ok(())
     }
+
+    fn set_error(&mut self, err: String) {
+        self.inner.borrow_mut().error = Some(err);
+    }
 }
 
 fn add_font(draw: &mut Draw, data: Vec<u8>) -> usize {
@@ -89,6 +111,7 @@ fn add_font(draw: &mut Draw, data: Vec<u8>) -> usize {
 
 struct InnerFont {
     id: Option<FontId>,
+    error: Option<String>,
 }
 
 #[inline]
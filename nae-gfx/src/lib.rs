@@ -155,20 +155,63 @@ impl GlowValue for StencilAction {
 
 mod shader;
 
-#[cfg(all(not(target_arch = "wasm32"), not(feature = "sdl")))]
+#[cfg(all(
+    not(target_arch = "wasm32"),
+    not(feature = "sdl"),
+    not(feature = "headless")
+))]
 use glutin::{PossiblyCurrent, WindowedContext};
 
 #[cfg(target_arch = "wasm32")]
 type Device = web_sys::HtmlCanvasElement;
 
-#[cfg(all(not(target_arch = "wasm32"), not(feature = "sdl")))]
+#[cfg(all(
+    not(target_arch = "wasm32"),
+    not(feature = "sdl"),
+    not(feature = "headless")
+))]
 type Device = WindowedContext<PossiblyCurrent>;
 
-#[cfg(all(not(target_arch = "wasm32"), feature = "sdl"))]
+#[cfg(all(
+    not(target_arch = "wasm32"),
+    feature = "sdl",
+    not(feature = "headless")
+))]
 type Device = sdl2::video::Window;
 
+#[cfg(all(not(target_arch = "wasm32"), feature = "headless"))]
+type Device = HeadlessDevice;
+
+/// Offscreen GL context used by the headless backend
+#[cfg(all(not(target_arch = "wasm32"), feature = "headless"))]
+pub struct HeadlessDevice {
+    pub ctx: glutin::Context<glutin::PossiblyCurrent>,
+    pub width: i32,
+    pub height: i32,
+}
+
 pub(crate) type GlContext = Rc<Context>;
 
+/// Counters of the work sent to the GPU since the last reset
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GfxStats {
+    pub draw_calls: u32,
+    /// Vertices drawn, indexed draws count each index as a vertex
+    pub vertices: u32,
+    pub texture_binds: u32,
+    pub color_flushes: u32,
+    pub image_flushes: u32,
+    pub pattern_flushes: u32,
+    pub text_flushes: u32,
+}
+
+impl GfxStats {
+    /// Batches sent by all the batchers
+    pub fn flushes(&self) -> u32 {
+        self.color_flushes + self.image_flushes + self.pattern_flushes + self.text_flushes
+    }
+}
+
 pub struct Graphics {
     pub(crate) gl: GlContext,
     pub(crate) gfx_api: GraphicsAPI,
@@ -177,10 +220,12 @@ pub struct Graphics {
     indices_in_use: bool,
     width: f32,
     height: f32,
+    offset: (f32, f32),
     running: bool,
     draw_calls: u32,
     last_pass_draw_calls: u32,
     render_target: Option<RenderTarget>,
+    pub(crate) stats: GfxStats,
 
     #[cfg(feature = "sdl")]
     _sdl_gl: Option<sdl2::video::GLContext>,
@@ -268,7 +313,11 @@ fn default_api() -> GraphicsAPI {
     GraphicsAPI::OpenGl3_3
 }
 
-#[cfg(all(not(target_arch = "wasm32"), not(feature = "sdl")))]
+#[cfg(all(
+    not(target_arch = "wasm32"),
+    not(feature = "sdl"),
+    not(feature = "headless")
+))]
 fn get_device_info(device: &WindowedContext<PossiblyCurrent>) -> Result<DeviceInfo, String> {
     let win: &glutin::window::Window = device.window();
     let size = win.inner_size();
@@ -286,7 +335,11 @@ fn get_device_info(device: &WindowedContext<PossiblyCurrent>) -> Result<DeviceIn
     })
 }
 
-#[cfg(all(not(target_arch = "wasm32"), feature = "sdl"))]
+#[cfg(all(
+    not(target_arch = "wasm32"),
+    feature = "sdl",
+    not(feature = "headless")
+))]
 fn get_device_info(device: &sdl2::video::Window) -> Result<DeviceInfo, String> {
     let size = device.drawable_size();
     let width = size.0 as _;
@@ -319,6 +372,23 @@ fn get_device_info(device: &sdl2::video::Window) -> Result<DeviceInfo, String> {
     })
 }
 
+#[cfg(all(not(target_arch = "wasm32"), feature = "headless"))]
+fn get_device_info(device: &HeadlessDevice) -> Result<DeviceInfo, String> {
+    let ctx = Rc::new(glow::Context::from_loader_function(|s| {
+        device.ctx.get_proc_address(s) as *const _
+    }));
+    let api = default_api();
+    Ok(DeviceInfo {
+        width: device.width,
+        height: device.height,
+        ctx,
+        api,
+
+        #[cfg(feature = "sdl")]
+        _sdl_gl: None,
+    })
+}
+
 impl Graphics {
     pub fn new(device: &Device) -> Result<Self, String> {
         let info = get_device_info(device)?; //TODO return webgl driver
@@ -333,11 +403,13 @@ impl Graphics {
             gl,
             width: info.width as _,
             height: info.height as _,
+            offset: (0.0, 0.0),
             running: false,
             gfx_api: info.api,
             indices_in_use: false,
             draw_calls: 0,
             last_pass_draw_calls: 0,
+            stats: Default::default(),
             index_type,
             render_target: None,
             pipeline: None,
@@ -363,6 +435,15 @@ impl Graphics {
         self.last_pass_draw_calls
     }
 
+    /// Returns the counters accumulated since the last reset
+    pub fn stats(&self) -> GfxStats {
+        self.stats
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = Default::default();
+    }
+
     pub fn clear(&mut self, opts: &ClearOptions) {
         let mut mask = 0;
         unsafe {
@@ -404,6 +485,11 @@ impl Graphics {
             self.render_target = target.cloned();
         }
     }
+
+    /// Set the position of the drawable area on the window, the space around it is cleared to black
+    pub fn set_offset(&mut self, x: f32, y: f32) {
+        self.offset = (x, y);
+    }
 }
 
 impl BaseGfx for Graphics {
@@ -437,20 +523,38 @@ impl BaseGfx for Graphics {
         self.running = true;
 
         unsafe {
-            let (width, height) = match &self.render_target {
+            let (x, y, width, height) = match &self.render_target {
                 Some(rt) => {
                     self.gl.bind_framebuffer(glow::FRAMEBUFFER, Some(rt.raw()));
                     // self.gl.draw_buffer(glow::COLOR_ATTACHMENT0);
-                    (rt.width(), rt.height())
+                    (0.0, 0.0, rt.width(), rt.height())
                 }
                 None => {
                     self.gl.bind_framebuffer(glow::FRAMEBUFFER, None);
-                    (self.width, self.height)
+                    (self.offset.0, self.offset.1, self.width, self.height)
                 }
             };
 
-            self.viewport(0.0, 0.0, width, height);
+            self.viewport(x, y, width, height);
+
+            let letterbox = x != 0.0 || y != 0.0;
+            if letterbox {
+                if opts.color.is_some() {
+                    self.gl.clear_color(0.0, 0.0, 0.0, 1.0);
+                    self.gl.clear(glow::COLOR_BUFFER_BIT);
+                }
+
+                // keep the clear inside the drawable area
+                self.gl.enable(glow::SCISSOR_TEST);
+                self.gl
+                    .scissor(x as i32, y as i32, width as i32, height as i32);
+            }
+
             self.clear(opts);
+
+            if letterbox {
+                self.gl.disable(glow::SCISSOR_TEST);
+            }
         }
     }
 
@@ -481,6 +585,8 @@ impl BaseGfx for Graphics {
             self.gl.bind_texture(glow::TEXTURE_2D, tex.raw());
             self.bind_uniform(location, &(slot as i32));
         }
+
+        self.stats.texture_binds += 1;
     }
 
     fn end(&mut self) {
@@ -541,6 +647,8 @@ impl BaseGfx for Graphics {
         }
 
         self.draw_calls += 1;
+        self.stats.draw_calls += 1;
+        self.stats.vertices += count.max(0) as u32;
     }
 }
 
@@ -1,7 +1,10 @@
 use crate::{GlContext, GlowValue, Graphics, TextureKey};
 use glow::HasContext;
 use nae_core::math::Rect;
-use nae_core::{BaseApp, BaseGfx, BaseSystem, Resource, TextureFilter, TextureFormat};
+use nae_core::window::RgbaImage;
+use nae_core::{
+    BaseApp, BaseGfx, BaseSystem, Resource, ResourceState, TextureFilter, TextureFormat,
+};
 use std::cell::RefCell;
 use std::rc::Rc;
 
@@ -15,6 +18,7 @@ struct InnerTexture {
     internal_format: TextureFormat,
     min_filter: TextureFilter,
     mag_filter: TextureFilter,
+    error: Option<String>,
 }
 
 impl Drop for InnerTexture {
@@ -65,6 +69,7 @@ where
             min_filter: opts.min_filter,
             mag_filter: opts.mag_filter,
             buffer: vec![],
+            error: None,
         };
 
         Ok(Texture {
@@ -98,13 +103,22 @@ where
             &opts,
         )?;
 
-        inner.texture = Some(texture);
+        if let Some(old) = inner.texture.replace(texture) {
+            unsafe {
+                inner.gl.delete_texture(old);
+            }
+        }
         inner.buffer = raw_data;
         inner.width = width;
         inner.height = height;
+        inner.error = None;
 
         Ok(())
     }
+
+    fn set_error(&mut self, err: String) {
+        self.inner.borrow_mut().error = Some(err);
+    }
 }
 
 impl Texture {
@@ -113,6 +127,16 @@ impl Texture {
         self.inner.borrow().texture.is_some()
     }
 
+    /// Returns the loading state of the resource
+    pub fn state(&self) -> ResourceState {
+        let inner = self.inner.borrow();
+        match (&inner.texture, &inner.error) {
+            (Some(_), _) => ResourceState::Loaded,
+            (_, Some(err)) => ResourceState::Failed(err.clone()),
+            _ => ResourceState::Pending,
+        }
+    }
+
     /// Returns the current frame
     pub fn frame(&self) -> Rect {
         self.frame.clone().unwrap_or(self.inner.borrow().frame())
@@ -178,6 +202,30 @@ impl Texture {
         Ok(texture)
     }
 
+    /// Create a new texture from the pixels of an image
+    pub fn from_rgba_image<T, S>(app: &mut T, image: &RgbaImage) -> Result<Self, String>
+    where
+        T: BaseApp<System = S>,
+        S: BaseSystem<Graphics = Graphics>,
+    {
+        let width = image.width as i32;
+        let height = image.height as i32;
+        let texture = Self::from(app, width, height, Default::default())?;
+        {
+            let mut inner = texture.inner.borrow_mut();
+            let opts = TextureOptions::default();
+            let raw = create_texture(&inner.gl, width, height, &image.data, 4, &opts)?;
+            if let Some(old) = inner.texture.replace(raw) {
+                unsafe {
+                    inner.gl.delete_texture(old);
+                }
+            }
+            inner.buffer = image.data.clone();
+        }
+
+        Ok(texture)
+    }
+
     /// Width of the base texture without the current frame's size
     pub fn base_width(&self) -> f32 {
         self.inner.borrow().width as _
@@ -188,6 +236,16 @@ impl Texture {
         self.inner.borrow().height as _
     }
 
+    /// Returns a copy of the loaded pixels, it's None if the texture wasn't loaded from an image
+    pub fn to_rgba_image(&self) -> Option<RgbaImage> {
+        let inner = self.inner.borrow();
+        if inner.buffer.is_empty() {
+            return None;
+        }
+
+        RgbaImage::new(inner.width as _, inner.height as _, inner.buffer.clone()).ok()
+    }
+
     pub(crate) fn raw(&self) -> Option<TextureKey> {
         self.inner.borrow().texture
     }
@@ -388,6 +446,7 @@ pub(crate) fn texture_from_gl_context(
         min_filter: opts.min_filter,
         mag_filter: opts.mag_filter,
         buffer: vec![],
+        error: None,
     };
 
     Ok(Texture {
@@ -0,0 +1,307 @@
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use hashbrown::HashMap;
+use std::convert::TryInto;
+use std::io::{Read, Write};
+
+/// Bytes at the start of every pack
+pub const MAGIC: &[u8; 8] = b"NAEPACK\0";
+
+/// Version of the format written by the builder
+pub const VERSION: u32 = 1;
+
+const FLAG_DEFLATE: u8 = 1;
+
+/// File stored on a pack
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackEntry {
+    pub path: String,
+    /// Size of the file once decompressed
+    pub size: u64,
+    pub compressed: bool,
+    offset: u64,
+    stored_size: u64,
+}
+
+/// Single file containing many files indexed by their virtual path.
+///
+/// The layout (little endian) is: magic, version (u32), number of entries (u32),
+/// an index with the path (u16 length + utf8), offset (u64), stored size (u64),
+/// size (u64) and flags (u8) of each file, and then the data of the files.
+pub struct Pack {
+    data: Vec<u8>,
+    data_start: usize,
+    entries: HashMap<String, PackEntry>,
+}
+
+impl Pack {
+    /// Read the index of a pack, the files are read from the data when they are requested
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self, String> {
+        let mut reader = ByteReader {
+            data: &data,
+            pos: 0,
+        };
+
+        if reader.bytes(MAGIC.len())? != MAGIC {
+            return Err("Invalid asset pack header".to_string());
+        }
+
+        let version = reader.u32()?;
+        if version != VERSION {
+            return Err(format!("Unsupported asset pack version {}", version));
+        }
+
+        let len = reader.u32()?;
+        let mut entries = HashMap::new();
+        for _ in 0..len {
+            let path_len = reader.u16()? as usize;
+            let path =
+                String::from_utf8(reader.bytes(path_len)?.to_vec()).map_err(|e| e.to_string())?;
+
+            let entry = PackEntry {
+                offset: reader.u64()?,
+                stored_size: reader.u64()?,
+                size: reader.u64()?,
+                compressed: reader.u8()? & FLAG_DEFLATE != 0,
+                path: path.clone(),
+            };
+
+            entries.insert(path, entry);
+        }
+
+        let data_start = reader.pos;
+        let pack = Self {
+            data,
+            data_start,
+            entries,
+        };
+
+        if let Some(entry) = pack.entries.values().find(|e| pack.range(e).is_none()) {
+            return Err(format!("Invalid asset pack entry '{}'", entry.path));
+        }
+
+        Ok(pack)
+    }
+
+    pub fn contains(&self, path: &str) -> bool {
+        self.entries.contains_key(path)
+    }
+
+    pub fn entry(&self, path: &str) -> Option<&PackEntry> {
+        self.entries.get(path)
+    }
+
+    /// Files stored on the pack
+    pub fn entries(&self) -> impl Iterator<Item = &PackEntry> {
+        self.entries.values()
+    }
+
+    /// Returns the content of a file, decompressed if it's needed
+    pub fn read(&self, path: &str) -> Result<Vec<u8>, String> {
+        let entry = self
+            .entries
+            .get(path)
+            .ok_or_else(|| format!("File '{}' not found on the asset pack", path))?;
+
+        // the ranges are checked when the pack is created
+        let stored = &self.data[self.range(entry).unwrap()];
+        if !entry.compressed {
+            return Ok(stored.to_vec());
+        }
+
+        // the size on the header is not trusted, the output is limited to it instead
+        let mut data = vec![];
+        DeflateDecoder::new(stored)
+            .take(entry.size.saturating_add(1))
+            .read_to_end(&mut data)
+            .map_err(|e| e.to_string())?;
+
+        if data.len() as u64 != entry.size {
+            return Err(format!("File '{}' is corrupted on the asset pack", path));
+        }
+
+        Ok(data)
+    }
+
+    fn range(&self, entry: &PackEntry) -> Option<std::ops::Range<usize>> {
+        let start = self.data_start.checked_add(entry.offset.try_into().ok()?)?;
+        let end = start.checked_add(entry.stored_size.try_into().ok()?)?;
+        if end > self.data.len() {
+            return None;
+        }
+
+        Some(start..end)
+    }
+}
+
+/// Create packs from files
+pub struct PackBuilder {
+    files: Vec<(String, Vec<u8>)>,
+    compress: bool,
+}
+
+impl PackBuilder {
+    pub fn new() -> Self {
+        Self {
+            files: vec![],
+            compress: true,
+        }
+    }
+
+    /// Compress the files with deflate, only if it makes them smaller. Enabled by default
+    pub fn compress(mut self, compress: bool) -> Self {
+        self.compress = compress;
+        self
+    }
+
+    /// Add a file using the path it will have on the pack
+    pub fn add(&mut self, path: &str, data: Vec<u8>) -> Result<(), String> {
+        let path = path.replace('\\', "/").trim_start_matches('/').to_string();
+        if path.len() > u16::MAX as usize {
+            return Err(format!("The path '{}' is too long", path));
+        }
+
+        if self.files.iter().any(|(p, _)| *p == path) {
+            return Err(format!("The file '{}' is already on the pack", path));
+        }
+
+        self.files.push((path, data));
+        Ok(())
+    }
+
+    /// Returns the bytes of the pack
+    pub fn build(&self) -> Result<Vec<u8>, String> {
+        let mut index = vec![];
+        let mut blobs = vec![];
+        for (path, data) in &self.files {
+            let compressed = if self.compress {
+                Some(deflate(data)?).filter(|c| c.len() < data.len())
+            } else {
+                None
+            };
+
+            let flags = if compressed.is_some() {
+                FLAG_DEFLATE
+            } else {
+                0
+            };
+            let stored = compressed.as_ref().unwrap_or(data);
+
+            index.extend_from_slice(&(path.len() as u16).to_le_bytes());
+            index.extend_from_slice(path.as_bytes());
+            index.extend_from_slice(&(blobs.len() as u64).to_le_bytes());
+            index.extend_from_slice(&(stored.len() as u64).to_le_bytes());
+            index.extend_from_slice(&(data.len() as u64).to_le_bytes());
+            index.push(flags);
+            blobs.extend_from_slice(stored);
+        }
+
+        let mut pack = Vec::with_capacity(16 + index.len() + blobs.len());
+        pack.extend_from_slice(MAGIC);
+        pack.extend_from_slice(&VERSION.to_le_bytes());
+        pack.extend_from_slice(&(self.files.len() as u32).to_le_bytes());
+        pack.extend_from_slice(&index);
+        pack.extend_from_slice(&blobs);
+        Ok(pack)
+    }
+}
+
+impl Default for PackBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn deflate(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut encoder = DeflateEncoder::new(vec![], Compression::best());
+    encoder.write_all(data).map_err(|e| e.to_string())?;
+    encoder.finish().map_err(|e| e.to_string())
+}
+
+struct ByteReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self.pos + len;
+        if end > self.data.len() {
+            return Err("Unexpected end of the asset pack".to_string());
+        }
+
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        Ok(u16::from_le_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, String> {
+        Ok(u64::from_le_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::*;
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_pack() {
+        let text = "lorem ipsum ".repeat(100).into_bytes();
+        let mut builder = PackBuilder::new();
+        builder.add("text.txt", text.clone()).unwrap();
+        builder.add("sprites\\hero.png", vec![1, 2, 3]).unwrap();
+        assert!(builder.add("/text.txt", vec![]).is_err());
+
+        let pack = Pack::from_bytes(builder.build().unwrap()).unwrap();
+        assert!(pack.entry("text.txt").unwrap().compressed);
+        assert!(!pack.entry("sprites/hero.png").unwrap().compressed);
+        assert_eq!(pack.read("text.txt"), Ok(text));
+        assert_eq!(pack.read("sprites/hero.png"), Ok(vec![1, 2, 3]));
+        assert!(pack.read("missing.png").is_err());
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_invalid_pack() {
+        assert!(Pack::from_bytes(b"NAEPACK".to_vec()).is_err());
+
+        let mut builder = PackBuilder::new().compress(false);
+        builder.add("a.txt", vec![0; 10]).unwrap();
+        let mut data = builder.build().unwrap();
+        data.truncate(data.len() - 1);
+        assert!(Pack::from_bytes(data).is_err());
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_wrong_size() {
+        let mut builder = PackBuilder::new();
+        builder
+            .add("text.txt", "lorem ipsum ".repeat(100).into_bytes())
+            .unwrap();
+        let mut pack = Pack::from_bytes(builder.build().unwrap()).unwrap();
+
+        pack.entries.get_mut("text.txt").unwrap().size = u64::MAX;
+        assert!(pack.read("text.txt").is_err());
+
+        pack.entries.get_mut("text.txt").unwrap().size = 10;
+        assert!(pack.read("text.txt").is_err());
+    }
+}
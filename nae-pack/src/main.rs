@@ -0,0 +1,67 @@
+use nae_pack::PackBuilder;
+use std::env;
+use std::error::Error;
+use std::path::Path;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let compress = !args.iter().any(|arg| arg == "--no-compress");
+    let paths: Vec<&String> = args.iter().filter(|arg| !arg.starts_with("--")).collect();
+    if paths.len() != 2 {
+        eprintln!("Usage: nae-pack <directory> <output file> [--no-compress]");
+        std::process::exit(1);
+    }
+
+    if let Err(e) = create_pack(paths[0], paths[1], compress) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn create_pack(directory: &str, output: &str, compress: bool) -> Result<(), Box<dyn Error>> {
+    let root = Path::new(directory);
+    let mut builder = PackBuilder::new().compress(compress);
+    let mut files = 0;
+    add_dir(&mut builder, root, root, &mut files)?;
+
+    let pack = builder.build()?;
+    std::fs::write(output, &pack)?;
+    println!(
+        "Packed {} files into {} ({} bytes)",
+        files,
+        output,
+        pack.len()
+    );
+
+    Ok(())
+}
+
+fn add_dir(
+    builder: &mut PackBuilder,
+    root: &Path,
+    dir: &Path,
+    files: &mut usize,
+) -> Result<(), Box<dyn Error>> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+
+        if entry.file_type()?.is_dir() {
+            add_dir(builder, root, &path, files)?;
+        } else {
+            // The paths on the pack are relative to the root directory
+            let name = path
+                .strip_prefix(root)?
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+                .join("/");
+
+            builder.add(&name, std::fs::read(&path)?)?;
+            println!("Added {}", name);
+            *files += 1;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,361 @@
+use crate::input::{Gamepads, Keyboard, Mouse};
+use hashbrown::{HashMap, HashSet};
+use nae_core::{GamepadAxis, GamepadButton, KeyCode, MouseButton};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Input that can trigger an action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    /// Button of any connected gamepad
+    Gamepad(GamepadButton),
+    /// All the inputs must be down at the same time (e.g. Ctrl+S)
+    Combo(Vec<Binding>),
+}
+
+impl Binding {
+    pub fn combo<T: Into<Binding>>(bindings: Vec<T>) -> Self {
+        Binding::Combo(bindings.into_iter().map(|b| b.into()).collect())
+    }
+
+    fn is_down(&self, input: &InputState) -> bool {
+        match self {
+            Binding::Key(key) => input.keyboard.is_down(*key),
+            Binding::Mouse(btn) => input.mouse.is_down(*btn),
+            Binding::Gamepad(btn) => input.gamepads.iter().any(|p| p.is_down(*btn)),
+            Binding::Combo(list) => !list.is_empty() && list.iter().all(|b| b.is_down(input)),
+        }
+    }
+
+    fn was_pressed(&self, input: &InputState) -> bool {
+        match self {
+            Binding::Key(key) => input.keyboard.was_pressed(*key),
+            Binding::Mouse(btn) => input.mouse.was_pressed(*btn),
+            Binding::Gamepad(btn) => input.gamepads.iter().any(|p| p.was_pressed(*btn)),
+            Binding::Combo(list) => {
+                self.is_down(input) && list.iter().any(|b| b.was_pressed(input))
+            }
+        }
+    }
+
+    fn was_released(&self, input: &InputState) -> bool {
+        match self {
+            Binding::Key(key) => input.keyboard.was_released(*key),
+            Binding::Mouse(btn) => input.mouse.was_released(*btn),
+            Binding::Gamepad(btn) => input.gamepads.iter().any(|p| p.was_released(*btn)),
+            Binding::Combo(list) => {
+                list.iter().any(|b| b.was_released(input))
+                    && list
+                        .iter()
+                        .all(|b| b.is_down(input) || b.was_released(input))
+            }
+        }
+    }
+
+    fn down_delta(&self, input: &InputState) -> f32 {
+        match self {
+            Binding::Key(key) => input.keyboard.down_delta(*key),
+            Binding::Mouse(btn) => input.mouse.down_delta(*btn),
+            Binding::Gamepad(btn) => input
+                .gamepads
+                .iter()
+                .fold(0.0, |acc, p| acc.max(p.down_delta(*btn))),
+            Binding::Combo(list) => list
+                .iter()
+                .map(|b| b.down_delta(input))
+                .fold(std::f32::MAX, f32::min),
+        }
+    }
+}
+
+impl From<KeyCode> for Binding {
+    fn from(key: KeyCode) -> Self {
+        Binding::Key(key)
+    }
+}
+
+impl From<MouseButton> for Binding {
+    fn from(btn: MouseButton) -> Self {
+        Binding::Mouse(btn)
+    }
+}
+
+impl From<GamepadButton> for Binding {
+    fn from(btn: GamepadButton) -> Self {
+        Binding::Gamepad(btn)
+    }
+}
+
+/// Input that gives a value from -1.0 to 1.0
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AxisBinding {
+    Keys {
+        negative: KeyCode,
+        positive: KeyCode,
+    },
+    GamepadButtons {
+        negative: GamepadButton,
+        positive: GamepadButton,
+    },
+    /// Axis of any connected gamepad with the deadzone applied
+    Gamepad(GamepadAxis),
+}
+
+impl AxisBinding {
+    fn value(&self, input: &InputState) -> f32 {
+        match self {
+            AxisBinding::Keys { negative, positive } => {
+                axis_from_buttons(Binding::Key(*negative), Binding::Key(*positive), input)
+            }
+            AxisBinding::GamepadButtons { negative, positive } => axis_from_buttons(
+                Binding::Gamepad(*negative),
+                Binding::Gamepad(*positive),
+                input,
+            ),
+            AxisBinding::Gamepad(axis) => input
+                .gamepads
+                .iter()
+                .map(|p| p.axis(*axis))
+                .fold(0.0, max_abs),
+        }
+    }
+}
+
+impl From<GamepadAxis> for AxisBinding {
+    fn from(axis: GamepadAxis) -> Self {
+        AxisBinding::Gamepad(axis)
+    }
+}
+
+fn axis_from_buttons(negative: Binding, positive: Binding, input: &InputState) -> f32 {
+    let mut value = 0.0;
+    if negative.is_down(input) {
+        value -= 1.0;
+    }
+    if positive.is_down(input) {
+        value += 1.0;
+    }
+    value
+}
+
+fn max_abs(a: f32, b: f32) -> f32 {
+    if b.abs() > a.abs() {
+        b
+    } else {
+        a
+    }
+}
+
+struct InputState<'a> {
+    keyboard: &'a Keyboard,
+    mouse: &'a Mouse,
+    gamepads: &'a Gamepads,
+}
+
+/// Named actions and their bindings, it can be serialized to let the players rebind them
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ActionMap {
+    buttons: BTreeMap<String, Vec<Binding>>,
+    axes: BTreeMap<String, Vec<AxisBinding>>,
+}
+
+impl ActionMap {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
+        serde_json::from_slice(data).map_err(|e| e.to_string())
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        serde_json::to_vec_pretty(self).map_err(|e| e.to_string())
+    }
+
+    /// Add a binding to the action, any of the bindings will trigger it
+    pub fn bind<B: Into<Binding>>(&mut self, action: &str, binding: B) -> &mut Self {
+        self.buttons
+            .entry(action.to_string())
+            .or_insert_with(Vec::new)
+            .push(binding.into());
+        self
+    }
+
+    /// Add a binding to the axis, the value with the greatest magnitude is used
+    pub fn bind_axis<B: Into<AxisBinding>>(&mut self, axis: &str, binding: B) -> &mut Self {
+        self.axes
+            .entry(axis.to_string())
+            .or_insert_with(Vec::new)
+            .push(binding.into());
+        self
+    }
+
+    /// Replace the bindings of the action
+    pub fn rebind<B: Into<Binding>>(&mut self, action: &str, binding: B) -> &mut Self {
+        self.buttons
+            .insert(action.to_string(), vec![binding.into()]);
+        self
+    }
+
+    /// Replace the bindings of the axis
+    pub fn rebind_axis<B: Into<AxisBinding>>(&mut self, axis: &str, binding: B) -> &mut Self {
+        self.axes.insert(axis.to_string(), vec![binding.into()]);
+        self
+    }
+
+    /// Remove the bindings of the action or axis
+    pub fn unbind(&mut self, name: &str) {
+        self.buttons.remove(name);
+        self.axes.remove(name);
+    }
+
+    pub fn bindings(&self, action: &str) -> &[Binding] {
+        self.buttons.get(action).map_or(&[][..], |b| b.as_slice())
+    }
+
+    pub fn axis_bindings(&self, axis: &str) -> &[AxisBinding] {
+        self.axes.get(axis).map_or(&[][..], |b| b.as_slice())
+    }
+}
+
+/// State of the actions on the current frame
+pub struct Actions {
+    /// Bindings used to compute the actions, it can be changed at any time
+    pub map: ActionMap,
+    pressed: HashSet<String>,
+    down: HashMap<String, f32>,
+    released: HashSet<String>,
+    axes: HashMap<String, f32>,
+}
+
+impl Actions {
+    pub(crate) fn new() -> Self {
+        Self {
+            map: ActionMap::new(),
+            pressed: HashSet::new(),
+            down: HashMap::new(),
+            released: HashSet::new(),
+            axes: HashMap::new(),
+        }
+    }
+
+    pub fn was_pressed(&self, action: &str) -> bool {
+        self.pressed.contains(action)
+    }
+
+    pub fn is_down(&self, action: &str) -> bool {
+        self.down.contains_key(action)
+    }
+
+    pub fn down_delta(&self, action: &str) -> f32 {
+        *self.down.get(action).unwrap_or(&0.0)
+    }
+
+    pub fn was_released(&self, action: &str) -> bool {
+        self.released.contains(action)
+    }
+
+    pub fn axis(&self, axis: &str) -> f32 {
+        *self.axes.get(axis).unwrap_or(&0.0)
+    }
+
+    pub(crate) fn update(&mut self, keyboard: &Keyboard, mouse: &Mouse, gamepads: &Gamepads) {
+        let input = InputState {
+            keyboard,
+            mouse,
+            gamepads,
+        };
+
+        self.pressed.clear();
+        self.down.clear();
+        self.released.clear();
+        self.axes.clear();
+
+        for (name, bindings) in self.map.buttons.iter() {
+            let mut down = false;
+            let mut delta = 0.0f32;
+            for b in bindings.iter().filter(|b| b.is_down(&input)) {
+                down = true;
+                delta = delta.max(b.down_delta(&input));
+            }
+
+            if down {
+                self.down.insert(name.clone(), delta);
+                if bindings.iter().any(|b| b.was_pressed(&input)) {
+                    self.pressed.insert(name.clone());
+                }
+            } else if bindings.iter().any(|b| b.was_released(&input)) {
+                self.released.insert(name.clone());
+            }
+        }
+
+        for (name, bindings) in self.map.axes.iter() {
+            let value = bindings.iter().map(|b| b.value(&input)).fold(0.0, max_abs);
+            self.axes.insert(name.clone(), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use nae_core::{Event, Modifiers};
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::*;
+
+    fn key_down(keyboard: &mut Keyboard, key: KeyCode) {
+        keyboard.process(&Event::KeyDown {
+            key,
+            modifiers: Modifiers::empty(),
+            is_repeat: false,
+        });
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_actions() {
+        let mut map = ActionMap::new();
+        map.bind("jump", KeyCode::Space)
+            .bind("save", Binding::combo(vec![KeyCode::LControl, KeyCode::S]))
+            .bind_axis(
+                "move_x",
+                AxisBinding::Keys {
+                    negative: KeyCode::A,
+                    positive: KeyCode::D,
+                },
+            );
+
+        let map = ActionMap::from_bytes(&map.to_bytes().unwrap()).unwrap();
+        let mut actions = Actions::new();
+        actions.map = map;
+
+        let mut keyboard = Keyboard::new();
+        let mouse = Mouse::new();
+        let gamepads = Gamepads::new();
+        key_down(&mut keyboard, KeyCode::S);
+        key_down(&mut keyboard, KeyCode::D);
+        actions.update(&keyboard, &mouse, &gamepads);
+        assert!(!actions.was_pressed("save"));
+        assert_eq!(actions.axis("move_x"), 1.0);
+
+        keyboard.clear();
+        key_down(&mut keyboard, KeyCode::LControl);
+        key_down(&mut keyboard, KeyCode::Space);
+        actions.update(&keyboard, &mouse, &gamepads);
+        assert!(actions.was_pressed("save"));
+        assert!(actions.was_pressed("jump"));
+
+        keyboard.clear();
+        keyboard.process(&Event::KeyUp {
+            key: KeyCode::Space,
+            modifiers: Modifiers::CTRL,
+        });
+        actions.update(&keyboard, &mouse, &gamepads);
+        assert!(actions.is_down("save"));
+        assert!(!actions.is_down("jump"));
+        assert!(actions.was_released("jump"));
+    }
+}
@@ -1,8 +1,20 @@
-use crate::input::{Keyboard, Mouse};
-use crate::res::{ResourceLoaderManager, ResourceParser};
+use crate::action::Actions;
+use crate::file::{normalize_key, Vfs};
+use crate::gesture::GestureRecognizer;
+use crate::input::{Gamepads, Keyboard, Mouse, Touches};
+use crate::plugin::{Plugin, Plugins};
+use crate::profiler::{Phase, Profiler};
+use crate::replay::{InputRecord, Replay};
+use crate::res::{
+    HotReloader, LoadedResource, ResourceCache, ResourceGroup, ResourceKey, ResourceLoaderManager,
+    ResourceParser, DEFAULT_GROUP,
+};
+use crate::sequence::InputBuffer;
 use backend::*;
+use nae_core::window::{BaseWindow, CursorIcon, RgbaImage};
 use nae_core::*;
 use nae_core::{BaseSystem, BuilderOpts, Event};
+use std::any::TypeId;
 use std::cell::RefCell;
 use std::cell::RefMut;
 use std::collections::VecDeque;
@@ -12,14 +24,31 @@ use std::rc::Rc;
 
 pub struct App {
     resources: ResourceLoaderManager,
+    cache: ResourceCache,
+    reloader: Option<HotReloader>,
     sys: System,
     fps: VecDeque<f64>,
     last_time: u64,
+    pub(crate) plugins: Plugins,
+    replay: Replay,
 
     pub delta: f32,
     pub mouse: Mouse,
     pub keyboard: Keyboard,
+    pub gamepads: Gamepads,
+    pub touches: Touches,
+    pub gestures: GestureRecognizer,
+    /// Last button events used to match double taps and sequences
+    pub input_buffer: InputBuffer,
+    pub actions: Actions,
+    pub profiler: Profiler,
+    /// Mounts used to resolve the files loaded as resources
+    pub vfs: Vfs,
     pub time: f32,
+
+    /// Interpolation value (0.0 to 1.0) between the last two fixed updates.
+    /// It's always 1.0 if the fixed timestep is not enabled
+    pub alpha: f32,
 }
 
 impl BaseApp for App {
@@ -31,12 +60,151 @@ impl BaseApp for App {
 }
 
 impl App {
+    /// Load a resource from a file, the same handle is returned for the same file and type
+    /// until it's unloaded. A resource that failed is loaded again when it's requested
     pub fn load_resource<T>(&mut self, file: &str) -> Result<T, String>
     where
         T: Resource<Self> + ResourceParser<App = App> + 'static,
     {
+        let group = self
+            .resources
+            .parsing_group()
+            .unwrap_or(DEFAULT_GROUP)
+            .to_string();
+
+        self.load_resource_in(&group, file)
+    }
+
+    /// Load a resource as part of a group to know when all of them are loaded
+    pub fn load_resource_in<T>(&mut self, group: &str, file: &str) -> Result<T, String>
+    where
+        T: Resource<Self> + ResourceParser<App = App> + 'static,
+    {
+        let key = resource_key::<T>(file);
+        if let Some(res) = self.cache.get::<T>(&key, group) {
+            let loaded = self.cache.loaded(&key);
+            self.resources.add_to_group(&key, group, loaded);
+            return Ok(res);
+        }
+
         let res = T::prepare(self, file)?;
-        self.resources.add(file, Box::new(res.clone()))?;
+        let future = self.vfs.load(file);
+        self.cache.insert(key.clone(), group, &res);
+        self.resources
+            .add(file, key, group, Box::new(res.clone()), future)?;
+        Ok(res)
+    }
+
+    /// Release a reference to the resource, when there are no more references it's removed
+    /// from the cache and cancelled if it's still loading. Returns true if it was removed.
+    /// The memory is freed once all the handles are dropped
+    pub fn unload_resource<T>(&mut self, file: &str) -> bool
+    where
+        T: Resource<Self> + 'static,
+    {
+        let key = resource_key::<T>(file);
+        let group = self.cache.groups(&key).last().cloned();
+        let removed = self.cache.release(&key);
+        if removed {
+            self.forget_resource(&key);
+        } else if let Some(group) = group {
+            // the group doesn't wait for the resources it doesn't reference anymore
+            self.resources.remove_from_group(&key, &group);
+        }
+
+        removed
+    }
+
+    /// Number of times the resource was requested without being unloaded
+    pub fn resource_references<T>(&self, file: &str) -> usize
+    where
+        T: Resource<Self> + 'static,
+    {
+        self.cache.references(&resource_key::<T>(file))
+    }
+
+    /// Returns the loading state of a group
+    pub fn resource_group(&self, group: &str) -> Option<&ResourceGroup> {
+        self.resources.group(group)
+    }
+
+    /// Returns the name and the loading state of each group
+    pub fn resource_groups(&self) -> impl Iterator<Item = (&str, &ResourceGroup)> {
+        self.resources.groups()
+    }
+
+    /// Value from 0.0 to 1.0 with the progress of all the resources loaded
+    pub fn resource_progress(&self) -> f32 {
+        let total = self
+            .resources
+            .groups()
+            .fold(ResourceGroup::default(), |acc, (_, group)| ResourceGroup {
+                total: acc.total + group.total,
+                loaded: acc.loaded + group.loaded,
+                failed: acc.failed + group.failed,
+            });
+
+        total.progress()
+    }
+
+    /// Forget a group releasing the references of its resources, the ones without references
+    /// are removed from the cache and cancelled if they are still loading
+    pub fn unload_group(&mut self, group: &str) {
+        self.resources.remove_group(group);
+        for key in self.cache.release_group(group) {
+            self.forget_resource(&key);
+        }
+    }
+
+    /// Release the reference of a resource requested while parsing another one, like the
+    /// previous texture of an atlas reloaded with a different image
+    pub(crate) fn unload_dependency<T>(&mut self, file: &str) -> bool
+    where
+        T: Resource<Self> + 'static,
+    {
+        let key = resource_key::<T>(file);
+        let group = self
+            .resources
+            .parsing_group()
+            .unwrap_or(DEFAULT_GROUP)
+            .to_string();
+
+        let removed = self.cache.release_in(&key, &group);
+        if removed {
+            self.forget_resource(&key);
+        }
+
+        removed
+    }
+
+    /// Parse again a resource, the resources loaded meanwhile go to its first group
+    pub(crate) fn reparse_resource(
+        &mut self,
+        key: &ResourceKey,
+        parser: &mut dyn ResourceParser<App = App>,
+        data: Vec<u8>,
+    ) -> Result<(), String> {
+        let group = self.cache.groups(key).first().cloned();
+        self.resources.set_parsing_group(group);
+        let result = parser.parse_resource(self, data);
+        self.resources.set_parsing_group(None);
+        result
+    }
+
+    fn forget_resource(&mut self, key: &ResourceKey) {
+        self.resources.cancel(key);
+        if let Some(reloader) = self.reloader.as_mut() {
+            reloader.unwatch(key);
+        }
+    }
+
+    /// Create a resource from bytes already loaded, like the data of a dropped file
+    pub fn load_resource_from_bytes<T>(&mut self, file: &str, data: Vec<u8>) -> Result<T, String>
+    where
+        T: Resource<Self>,
+    {
+        let mut res = T::prepare(self, file)?;
+        res.set_data(self, data)?;
         Ok(res)
     }
 
@@ -82,22 +250,199 @@ impl App {
     pub fn fullscreen(&mut self) -> bool {
         self.system().fullscreen()
     }
+
+    pub fn title(&mut self) -> String {
+        self.sys.window().title().to_string()
+    }
+
+    pub fn set_title(&mut self, title: &str) {
+        self.sys.window().set_title(title);
+    }
+
+    /// Returns the position of the window on the screen, it's always (0, 0) on the web target
+    pub fn position(&mut self) -> (i32, i32) {
+        self.sys.window().position()
+    }
+
+    pub fn set_position(&mut self, x: i32, y: i32) {
+        self.sys.window().set_position(x, y);
+    }
+
+    /// Change the window's size, the event `WindowResize` will be dispatched
+    pub fn set_size(&mut self, width: i32, height: i32) {
+        self.sys.window().set_size(width, height);
+    }
+
+    pub fn set_icon(&mut self, icon: RgbaImage) -> Result<(), String> {
+        self.sys.window().set_icon(Some(icon))
+    }
+
+    /// Use the pixels of a texture loaded from an image as the window's icon
+    pub fn set_icon_from_texture(&mut self, texture: &Texture) -> Result<(), String> {
+        let icon = texture
+            .to_rgba_image()
+            .ok_or("The texture doesn't have any pixel data loaded.".to_string())?;
+        self.set_icon(icon)
+    }
+
+    pub fn clear_icon(&mut self) -> Result<(), String> {
+        self.sys.window().set_icon(None)
+    }
+
+    pub fn cursor_visible(&mut self) -> bool {
+        self.sys.window().cursor_visible()
+    }
+
+    pub fn set_cursor_visible(&mut self, visible: bool) {
+        self.sys.window().set_cursor_visible(visible);
+    }
+
+    pub fn cursor(&mut self) -> CursorIcon {
+        self.sys.window().cursor()
+    }
+
+    pub fn set_cursor(&mut self, cursor: CursorIcon) {
+        self.sys.window().set_cursor(cursor);
+    }
+
+    pub fn pointer_locked(&mut self) -> bool {
+        self.sys.window().pointer_locked()
+    }
+
+    /// Hide and lock the mouse cursor to the window, use `mouse.motion()` to read the movement.
+    /// Browsers only allow locking the pointer after an user input event
+    pub fn set_pointer_lock(&mut self, lock: bool) -> Result<(), String> {
+        self.sys.window().set_pointer_lock(lock)
+    }
+
+    /// Use an image as the mouse cursor, the hotspot is the point of the image used as the position.
+    /// The winit backend hides the OS cursor and draws the image over the app at the mouse position.
+    /// The web target doesn't support custom cursors and returns an error
+    pub fn set_custom_cursor(
+        &mut self,
+        image: RgbaImage,
+        hot_x: u32,
+        hot_y: u32,
+    ) -> Result<(), String> {
+        self.sys.window().set_custom_cursor(image, hot_x, hot_y)
+    }
+
+    /// Returns the text on the clipboard. The web target only knows the text set by the app
+    /// or the last text pasted by the user
+    pub fn clipboard_text(&mut self) -> Option<String> {
+        self.sys.clipboard_text()
+    }
+
+    pub fn set_clipboard_text(&mut self, text: &str) -> Result<(), String> {
+        self.sys.set_clipboard_text(text)
+    }
+
+    /// Returns the first plugin of the type given.
+    /// Plugins are not reachable from inside their own hooks
+    pub fn plugin<T: Plugin + 'static>(&mut self) -> Option<&mut T> {
+        self.plugins.get_mut::<T>()
+    }
+
+    /// Start recording the events and delta time of each frame, stops any replay running
+    pub fn start_recording(&mut self) {
+        self.replay = Replay::Recording(InputRecord::new());
+    }
+
+    /// Stop the recording returning the frames recorded
+    pub fn stop_recording(&mut self) -> Option<InputRecord> {
+        match std::mem::replace(&mut self.replay, Replay::None) {
+            Replay::Recording(record) => Some(record),
+            replay => {
+                self.replay = replay;
+                None
+            }
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        match self.replay {
+            Replay::Recording(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Use the recorded events and delta time instead of the live input until the record ends
+    pub fn replay(&mut self, record: InputRecord) {
+        self.replay = Replay::Replaying { record, index: 0 };
+    }
+
+    pub fn stop_replay(&mut self) {
+        if self.is_replaying() {
+            self.replay = Replay::None;
+        }
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        match self.replay {
+            Replay::Replaying { .. } => true,
+            _ => false,
+        }
+    }
+
+    fn process_replay(&mut self) {
+        let delta = self.replay.process(self.sys.events(), self.delta);
+        self.time += delta - self.delta;
+        self.delta = delta;
+    }
 }
 
+type StateCallback<S> = Box<dyn FnOnce(&mut App) -> S>;
+type AppCallback<S> = Box<dyn FnMut(&mut App, &mut S)>;
+type EventCallback<S> = Box<dyn FnMut(&mut App, &mut S, Event)>;
+
 pub struct AppBuilder<S>
 where
     S: 'static,
 {
-    state_cb: fn(&mut App) -> S,
-    draw_callback: Option<fn(&mut App, &mut S)>,
-    update_callback: Option<fn(&mut App, &mut S)>,
-    start_callback: Option<fn(&mut App, &mut S)>,
-    event_callback: Option<fn(&mut App, &mut S, event: Event)>,
+    state_cb: Option<StateCallback<S>>,
+    draw_callback: Option<AppCallback<S>>,
+    update_callback: Option<AppCallback<S>>,
+    start_callback: Option<AppCallback<S>>,
+    event_callback: Option<EventCallback<S>>,
     options: BuilderOpts,
+    fixed_fps: Option<i32>,
+    max_fixed_steps: u32,
+    plugins: Plugins,
+    hot_reload: Option<f32>,
 }
 
 impl<S> AppBuilder<S> {
     pub fn build(&mut self) -> Result<(), String> {
+        let (app, state, update, draw) = self.prepare()?;
+        backend::run(app, state, update, draw)?;
+
+        Ok(())
+    }
+
+    /// Build the app without running it, the returned runner needs to be stepped manually.
+    /// Use `--no-default-features --features headless` to not depend on winit on CI machines
+    #[cfg(feature = "headless")]
+    pub fn build_headless(&mut self) -> Result<HeadlessRunner<App, S>, String> {
+        let (app, state, update, draw) = self.prepare()?;
+        Ok(HeadlessRunner::new(app, state, update, draw))
+    }
+
+    fn prepare(
+        &mut self,
+    ) -> Result<
+        (
+            App,
+            S,
+            impl FnMut(&mut App, &mut S) + 'static,
+            impl FnMut(&mut App, &mut S) + 'static,
+        ),
+        String,
+    > {
+        let state_cb = self
+            .state_cb
+            .take()
+            .ok_or_else(|| "The app was already built.".to_string())?;
+
         let sys = System::new(self.options.clone())?;
         let width = sys.width();
         let height = sys.height();
@@ -108,44 +453,123 @@ impl<S> AppBuilder<S> {
         let mut app = App {
             sys: sys,
             resources: ResourceLoaderManager::new(),
+            cache: ResourceCache::new(),
+            reloader: self.hot_reload.map(HotReloader::new),
             fps: fps,
             last_time: date_now(),
+            plugins: std::mem::replace(&mut self.plugins, Plugins::new()),
+            replay: Replay::None,
             delta: 0.0,
             time: 0.0,
+            alpha: 1.0,
             mouse: Mouse::new(),
             keyboard: Keyboard::new(),
+            gamepads: Gamepads::new(),
+            touches: Touches::new(),
+            gestures: GestureRecognizer::new(),
+            input_buffer: InputBuffer::new(),
+            actions: Actions::new(),
+            profiler: Profiler::new(),
+            vfs: Vfs::new(),
         };
 
         let dpi = app.dpi();
         app.draw().update_dpi(dpi);
 
-        let mut state = (self.state_cb)(&mut app);
-        let draw_cb = self.draw_callback.take().unwrap_or(|_, _| {});
-        let update_cb = self.update_callback.take().unwrap_or(|_, _| {});
-        let start_cb = self.start_callback.take().unwrap_or(|_, _| {});
-        let event_cb = self.event_callback.take().unwrap_or(|_, _, _| {});
-
+        let mut state = state_cb(&mut app);
+        let mut draw_cb = self
+            .draw_callback
+            .take()
+            .unwrap_or_else(|| Box::new(|_, _| {}));
+        let mut update_cb = self
+            .update_callback
+            .take()
+            .unwrap_or_else(|| Box::new(|_, _| {}));
+        let mut start_cb = self
+            .start_callback
+            .take()
+            .unwrap_or_else(|| Box::new(|_, _| {}));
+        let mut event_cb = self
+            .event_callback
+            .take()
+            .unwrap_or_else(|| Box::new(|_, _, _| {}));
+
+        Plugins::pre_start(&mut app);
         start_cb(&mut app, &mut state);
-        backend::run(
-            app,
-            state,
-            move |mut app, mut state| {
-                app.tick();
-                try_load_resources(&mut app).unwrap();
-                process_events(app, state, event_cb);
-                update_cb(&mut app, &mut state);
-            },
-            move |mut app, mut state| {
-                draw_cb(&mut app, &mut state);
-            },
-        )?;
+        Plugins::post_start(&mut app);
+
+        let mut fixed = self
+            .fixed_fps
+            .map(|fps| FixedTimestep::new(fps, self.max_fixed_steps));
+        let mut clear_input = true;
+
+        let update = move |app: &mut App, state: &mut S| {
+            app.profiler.begin_frame();
+            app.tick();
+            app.process_replay();
+
+            app.profiler.mark();
+            try_load_resources(app);
+            reload_resources(app);
+            app.profiler.measure(Phase::Resources);
+            process_events(app, state, &mut event_cb, clear_input);
+            app.profiler.measure(Phase::Events);
+
+            match &mut fixed {
+                Some(fixed) => {
+                    let frame_delta = app.delta;
+                    let steps = fixed.advance(frame_delta);
+
+                    app.delta = fixed.step;
+                    for step in 0..steps {
+                        // pressed and released are only reported on the first update of the frame
+                        if step == 1 {
+                            clear_frame_input(app);
+                            app.actions.update(&app.keyboard, &app.mouse, &app.gamepads);
+                        }
+
+                        Plugins::pre_update(app);
+                        update_cb(app, state);
+                        Plugins::post_update(app);
+                    }
+
+                    app.delta = frame_delta;
+                    app.alpha = fixed.alpha();
+
+                    // keep the input until at least one fixed update can read it
+                    clear_input = steps > 0;
+                }
+                None => {
+                    Plugins::pre_update(app);
+                    update_cb(app, state);
+                    Plugins::post_update(app);
+                }
+            }
 
-        Ok(())
+            app.profiler.measure(Phase::Update);
+        };
+
+        let draw = move |app: &mut App, state: &mut S| {
+            app.profiler.mark();
+            Plugins::pre_draw(app);
+            draw_cb(app, state);
+            Plugins::post_draw(app);
+            app.sys.post_draw();
+            app.profiler.measure(Phase::Draw);
+
+            let stats = app.gfx().stats();
+            app.gfx().reset_stats();
+            app.profiler.end_frame(stats);
+        };
+
+        Ok((app, state, update, draw))
     }
 
+    /// Keep the aspect ratio of the initial size when the window is resized or maximized.
+    /// Native targets letterbox the drawable area and the web target constrains the canvas
     pub fn keep_window_aspect(&mut self) -> &mut Self {
-        //TODO just for wasm32, if it's enabled keep the canvas apsect/ratio when resizable or maximized is enabled
-        unimplemented!()
+        self.options.keep_aspect = true;
+        self
     }
 
     pub fn size(&mut self, width: i32, height: i32) -> &mut Self {
@@ -179,9 +603,9 @@ impl<S> AppBuilder<S> {
         self
     }
 
-    pub fn icon(&mut self) -> &mut Self {
-        //TODO set window icon
-        unimplemented!()
+    pub fn icon(&mut self, icon: RgbaImage) -> &mut Self {
+        self.options.icon = Some(icon);
+        self
     }
 
     pub fn resizable(&mut self) -> &mut Self {
@@ -189,40 +613,138 @@ impl<S> AppBuilder<S> {
         self
     }
 
-    pub fn draw(&mut self, cb: fn(&mut App, &mut S)) -> &mut Self {
-        self.draw_callback = Some(cb);
+    pub fn draw<F>(&mut self, cb: F) -> &mut Self
+    where
+        F: FnMut(&mut App, &mut S) + 'static,
+    {
+        self.draw_callback = Some(Box::new(cb));
         self
     }
 
-    pub fn start(&mut self, cb: fn(&mut App, &mut S)) -> &mut Self {
-        self.start_callback = Some(cb);
+    pub fn start<F>(&mut self, cb: F) -> &mut Self
+    where
+        F: FnMut(&mut App, &mut S) + 'static,
+    {
+        self.start_callback = Some(Box::new(cb));
         self
     }
 
-    pub fn update(&mut self, cb: fn(&mut App, &mut S)) -> &mut Self {
-        self.update_callback = Some(cb);
+    pub fn update<F>(&mut self, cb: F) -> &mut Self
+    where
+        F: FnMut(&mut App, &mut S) + 'static,
+    {
+        self.update_callback = Some(Box::new(cb));
         self
     }
 
-    pub fn event(&mut self, cb: fn(&mut App, &mut S, event: Event)) -> &mut Self {
-        self.event_callback = Some(cb);
+    pub fn event<F>(&mut self, cb: F) -> &mut Self
+    where
+        F: FnMut(&mut App, &mut S, Event) + 'static,
+    {
+        self.event_callback = Some(Box::new(cb));
         self
     }
 
     pub fn fps_target(&mut self, fps: i32) -> &mut Self {
-        //TODO implement this on the backends
         self.options.fps_target = Some(fps);
         self
     }
+
+    /// Run the update callback at a fixed rate, `App::alpha` can be used on draw to interpolate.
+    /// The keys pressed or released are only reported on the first update of each frame
+    pub fn fixed_timestep(&mut self, fps: i32) -> &mut Self {
+        self.fixed_fps = Some(fps);
+        self
+    }
+
+    /// Max number of fixed updates per frame used to catch up when the app is running slow
+    pub fn max_fixed_steps(&mut self, steps: u32) -> &mut Self {
+        self.max_fixed_steps = steps;
+        self
+    }
+
+    /// Parse again the resources loaded when their files change on the disk, checking them each
+    /// `interval` seconds. A `ResourceReloaded` event is dispatched for each resource reloaded.
+    /// Only the native platforms are supported
+    pub fn hot_reload(&mut self, interval: f32) -> &mut Self {
+        if cfg!(target_arch = "wasm32") {
+            log::warn!("Hot reloading is not supported on the web");
+        } else {
+            self.hot_reload = Some(interval);
+        }
+        self
+    }
+
+    /// Add a plugin, the hooks will be called in the same order the plugins were added
+    pub fn plugin<P: Plugin + 'static>(&mut self, plugin: P) -> &mut Self {
+        self.plugins.push(Box::new(plugin));
+        self
+    }
 }
 
-fn process_events<S>(app: &mut App, state: &mut S, cb: fn(&mut App, &mut S, Event)) {
-    app.mouse.clear();
-    app.keyboard.clear();
-    let mut events = app.sys.events().take_events();
-    for evt in events {
-        app.mouse.process(&evt, app.delta);
-        app.keyboard.process(&evt, app.delta);
+/// Accumulates the frame time to know how many fixed updates need to run
+struct FixedTimestep {
+    step: f32,
+    max_steps: u32,
+    accumulator: f32,
+}
+
+impl FixedTimestep {
+    fn new(fps: i32, max_steps: u32) -> Self {
+        Self {
+            step: 1.0 / fps.max(1) as f32,
+            max_steps: max_steps.max(1),
+            accumulator: 0.0,
+        }
+    }
+
+    fn advance(&mut self, delta: f32) -> u32 {
+        self.accumulator += delta;
+
+        let mut steps = 0;
+        while self.accumulator >= self.step && steps < self.max_steps {
+            self.accumulator -= self.step;
+            steps += 1;
+        }
+
+        // drop the time that can't be caught up to avoid a spiral of death
+        if self.accumulator >= self.step {
+            self.accumulator %= self.step;
+        }
+
+        steps
+    }
+
+    fn alpha(&self) -> f32 {
+        self.accumulator / self.step
+    }
+}
+
+fn process_events<S>(app: &mut App, state: &mut S, cb: &mut EventCallback<S>, clear_input: bool) {
+    if clear_input {
+        clear_frame_input(app);
+    }
+
+    app.mouse.tick(app.delta);
+    app.keyboard.tick(app.delta);
+    app.gamepads.tick(app.delta);
+    app.touches.tick(app.delta);
+    app.gestures.tick(app.delta);
+    app.input_buffer.tick(app.delta);
+
+    // events pushed while processing the queue (e.g. by plugins) are processed in this frame too
+    while let Some(evt) = app.sys.events().pop() {
+        let evt = match Plugins::event(app, evt) {
+            Some(evt) => evt,
+            _ => continue,
+        };
+
+        app.mouse.process(&evt);
+        app.keyboard.process(&evt);
+        app.gamepads.process(&evt);
+        app.touches.process(&evt);
+        app.gestures.process(&evt);
+        app.input_buffer.process(&evt);
 
         match evt {
             Event::WindowResize { width, height } => {
@@ -236,30 +758,113 @@ fn process_events<S>(app: &mut App, state: &mut S, cb: fn(&mut App, &mut S, Even
 
         cb(app, state, evt);
     }
+
+    app.actions.update(&app.keyboard, &app.mouse, &app.gamepads);
+}
+
+/// Clear the input that only lasts one frame, like the keys pressed or the mouse motion
+fn clear_frame_input(app: &mut App) {
+    app.mouse.clear();
+    app.keyboard.clear();
+    app.gamepads.clear();
+    app.touches.clear();
+    app.gestures.clear();
+    app.input_buffer.next_frame();
 }
 
-//TODO don't stop the loop, just return Vec<String> with the errors, and the user will decide what to do instead of stop the program
-fn try_load_resources(app: &mut App) -> Result<(), String> {
-    if let Some(mut assets_loaded) = app.resources.try_load()? {
-        while let Some((data, mut asset)) = assets_loaded.pop() {
-            asset.parse_resource(app, data)?;
+fn try_load_resources(app: &mut App) {
+    if let Some(mut assets_loaded) = app.resources.try_load() {
+        while let Some(loaded) = assets_loaded.pop() {
+            let LoadedResource {
+                file,
+                key,
+                groups,
+                result,
+                mut parser,
+            } = loaded;
+
+            // the resources loaded while parsing go to the first group that requested this one
+            app.resources.set_parsing_group(groups.first().cloned());
+            let result = result.and_then(|data| parser.parse_resource(app, data));
+            app.resources.set_parsing_group(None);
+            app.resources.finish_resource(&key, &groups, result.is_ok());
+            app.cache.set_loaded(&key, result.is_ok());
+
+            // failures are dispatched as events to let the user decide what to do
+            match result {
+                Ok(_) => {
+                    let path = app.vfs.disk_path(&file);
+                    if let (Some(reloader), Some(path)) = (app.reloader.as_mut(), path) {
+                        reloader.watch(file, key, path, parser);
+                    }
+                }
+                Err(error) => {
+                    parser.set_error(error.clone());
+                    app.sys.events().push(Event::ResourceFailed { file, error });
+                }
+            }
         }
     }
 
-    Ok(())
+    // the groups can be completed without loading anything, cancelling their resources
+    for group in app.resources.take_completed() {
+        app.sys.events().push(Event::ResourceGroupReady { group });
+    }
+}
+
+fn resource_key<T: 'static>(file: &str) -> ResourceKey {
+    (TypeId::of::<T>(), normalize_key(file))
+}
+
+fn reload_resources(app: &mut App) {
+    if let Some(mut reloader) = app.reloader.take() {
+        reloader.update(app);
+
+        // the resources released while reloading are not cached anymore
+        reloader.retain(|key| app.cache.references(key) > 0);
+        app.reloader = Some(reloader);
+    }
 }
 
 pub fn init() -> AppBuilder<()> {
     init_with(|_| ())
 }
 
-pub fn init_with<S>(cb: fn(&mut App) -> S) -> AppBuilder<S> {
+pub fn init_with<S, F>(cb: F) -> AppBuilder<S>
+where
+    F: FnOnce(&mut App) -> S + 'static,
+{
     AppBuilder {
-        state_cb: cb,
+        state_cb: Some(Box::new(cb)),
         draw_callback: None,
         update_callback: None,
         start_callback: None,
         event_callback: None,
         options: BuilderOpts::default(),
+        fixed_fps: None,
+        max_fixed_steps: 5,
+        plugins: Plugins::new(),
+        hot_reload: None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::*;
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_fixed_timestep() {
+        let mut fixed = FixedTimestep::new(10, 3);
+        assert_eq!(fixed.advance(0.05), 0);
+        assert_eq!(fixed.advance(0.06), 1);
+        assert!((fixed.alpha() - 0.1).abs() < 0.001);
+
+        //A long frame can't run more than the max steps
+        assert_eq!(fixed.advance(1.0), 3);
+        assert!(fixed.alpha() < 1.0);
     }
 }
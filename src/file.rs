@@ -1 +1,405 @@
-//Create a file sandbox to load files from a root directory, useful to export to mobile devices etc...
+use backend::load_file;
+use futures::{future, Async, Future, Poll};
+use hashbrown::HashMap;
+use nae_pack::Pack;
+use std::cell::RefCell;
+use std::io::{Cursor, Read};
+use std::rc::Rc;
+use zip::ZipArchive;
+
+/// Future returned when a file is read from the virtual file system
+pub type FileFuture = Box<dyn Future<Item = Vec<u8>, Error = String>>;
+
+/// Create a list of embedded files to mount, the paths are relative to the directory given,
+/// which is relative to the file where the macro is called like `include_bytes!`
+/// ```ignore
+/// app.vfs.mount_embedded("", embed_files!("assets", ["sprites/hero.png", "font.ttf"]), 0);
+/// ```
+#[macro_export]
+macro_rules! embed_files {
+    ($dir:literal, [$($file:literal),* $(,)?]) => {
+        vec![$(($file, &include_bytes!(concat!($dir, "/", $file))[..])),*]
+    };
+}
+
+enum Source {
+    /// Directory on the disk, or a url on the web
+    Dir(String),
+    Embedded(HashMap<String, &'static [u8]>),
+    Zip(ZipArchive<Cursor<Vec<u8>>>),
+    Pack(Rc<RefCell<PackState>>),
+}
+
+impl Source {
+    /// Returns if the source has the file, or `None` if it can't be known without loading it
+    fn has(&self, rel: &str) -> Option<bool> {
+        match self {
+            Source::Dir(_) => None,
+            Source::Embedded(files) => Some(files.contains_key(rel)),
+            Source::Zip(archive) => Some(archive.file_names().any(|name| name == rel)),
+            Source::Pack(state) => pack_has(state, rel),
+        }
+    }
+}
+
+/// Packs mounted from a file are loaded with the first file requested to them
+enum PackState {
+    Loading(FileFuture),
+    Ready(Pack),
+    Failed(String),
+}
+
+struct Mount {
+    root: String,
+    priority: i32,
+    source: Source,
+}
+
+/// Virtual file system, the paths are resolved through the mounts by priority
+/// and as raw paths if there are no mounts for them
+pub struct Vfs {
+    mounts: Vec<Mount>,
+}
+
+impl Vfs {
+    pub fn new() -> Self {
+        Self { mounts: vec![] }
+    }
+
+    /// Mount a directory (or a base url on the web) under the root given
+    pub fn mount_dir(&mut self, root: &str, dir: &str, priority: i32) {
+        let dir = dir.replace('\\', "/").trim_end_matches('/').to_string();
+        self.add(root, priority, Source::Dir(dir));
+    }
+
+    /// Mount files embedded on the binary, usually created with `embed_files!`
+    pub fn mount_embedded(&mut self, root: &str, files: Vec<(&str, &'static [u8])>, priority: i32) {
+        let files = files
+            .into_iter()
+            .map(|(path, data)| (normalize(path), data))
+            .collect();
+
+        self.add(root, priority, Source::Embedded(files));
+    }
+
+    /// Mount the content of a zip archive
+    pub fn mount_zip(&mut self, root: &str, data: Vec<u8>, priority: i32) -> Result<(), String> {
+        let archive = ZipArchive::new(Cursor::new(data)).map_err(|e| e.to_string())?;
+        self.add(root, priority, Source::Zip(archive));
+        Ok(())
+    }
+
+    /// Mount an asset pack already loaded
+    pub fn mount_pack(&mut self, root: &str, data: Vec<u8>, priority: i32) -> Result<(), String> {
+        let pack = Pack::from_bytes(data)?;
+        let state = PackState::Ready(pack);
+        self.add(root, priority, Source::Pack(Rc::new(RefCell::new(state))));
+        Ok(())
+    }
+
+    /// Mount an asset pack loaded from a file, it's read with a single request through the
+    /// current mounts and the files requested meanwhile wait for it
+    pub fn mount_pack_file(&mut self, root: &str, file: &str, priority: i32) {
+        let state = PackState::Loading(self.load(file));
+        self.add(root, priority, Source::Pack(Rc::new(RefCell::new(state))));
+    }
+
+    /// Remove the mounts using the root given
+    pub fn unmount(&mut self, root: &str) {
+        let root = normalize(root);
+        self.mounts.retain(|m| m.root != root);
+    }
+
+    /// Returns the virtual roots in the order they are resolved
+    pub fn roots(&self) -> Vec<&str> {
+        self.mounts.iter().map(|m| m.root.as_str()).collect()
+    }
+
+    /// Returns true if the path is inside an embedded, zip or pack mount. Directories and
+    /// packs still loading are not checked because it can't be done synchronously on the web
+    pub fn contains(&self, path: &str) -> bool {
+        let path = normalize(path);
+        self.mounts.iter().any(|m| match relative(&m.root, &path) {
+            Some(rel) => m.source.has(rel) == Some(true),
+            None => false,
+        })
+    }
+
+    /// Read a file, the mounts are tried in order until one of them has the file
+    pub fn load(&mut self, file: &str) -> FileFuture {
+        let path = normalize(file);
+        let mut candidates: Vec<FileFuture> = vec![];
+        let mut mounted = false;
+        for mount in self.mounts.iter_mut() {
+            let rel = match relative(&mount.root, &path) {
+                Some(rel) => rel,
+                None => continue,
+            };
+
+            mounted = true;
+
+            match &mut mount.source {
+                Source::Dir(dir) => {
+                    let file = if dir.is_empty() {
+                        rel.to_string()
+                    } else {
+                        format!("{}/{}", dir, rel)
+                    };
+                    candidates.push(Box::new(load_file(&file)));
+                }
+                Source::Embedded(files) => {
+                    if let Some(data) = files.get(rel) {
+                        return chain(candidates, Box::new(future::ok(data.to_vec())));
+                    }
+                }
+                Source::Zip(archive) => {
+                    if let Ok(mut file) = archive.by_name(rel) {
+                        let mut data = vec![];
+                        let result = file
+                            .read_to_end(&mut data)
+                            .map(|_| data)
+                            .map_err(|e| e.to_string());
+                        return chain(candidates, Box::new(future::result(result)));
+                    }
+                }
+                Source::Pack(state) => match pack_has(state, rel) {
+                    Some(true) => {
+                        let result = read_pack(state, rel);
+                        return chain(candidates, Box::new(future::result(result)));
+                    }
+                    Some(false) => {}
+                    None => {
+                        let state = state.clone();
+                        let rel = rel.to_string();
+                        let fut = future::poll_fn(move || poll_pack(&state, &rel));
+                        candidates.push(Box::new(fut));
+                    }
+                },
+            }
+        }
+
+        if !mounted {
+            return Box::new(load_file(file));
+        }
+
+        let err = format!("File '{}' not found on the mounts", path);
+        chain(candidates, Box::new(future::err(err)))
+    }
+
+    /// Returns the path on the disk used to load the file, if it's not embedded or on a zip
+    pub(crate) fn disk_path(&self, file: &str) -> Option<String> {
+        let path = normalize(file);
+        let mut mounted = false;
+        for mount in self.mounts.iter() {
+            let rel = match relative(&mount.root, &path) {
+                Some(rel) => rel,
+                None => continue,
+            };
+
+            mounted = true;
+
+            match &mount.source {
+                Source::Dir(dir) => {
+                    let file = if dir.is_empty() {
+                        rel.to_string()
+                    } else {
+                        format!("{}/{}", dir, rel)
+                    };
+
+                    if std::path::Path::new(&file).exists() {
+                        return Some(file);
+                    }
+                }
+                source => {
+                    if source.has(rel) != Some(false) {
+                        return None;
+                    }
+                }
+            }
+        }
+
+        if mounted {
+            None
+        } else {
+            Some(file.to_string())
+        }
+    }
+
+    fn add(&mut self, root: &str, priority: i32, source: Source) {
+        // the last mount added goes first between mounts with the same priority
+        let index = self
+            .mounts
+            .iter()
+            .position(|m| m.priority <= priority)
+            .unwrap_or(self.mounts.len());
+
+        self.mounts.insert(
+            index,
+            Mount {
+                root: normalize(root),
+                priority,
+                source,
+            },
+        );
+    }
+}
+
+impl Default for Vfs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn pack_has(state: &RefCell<PackState>, path: &str) -> Option<bool> {
+    match &*state.borrow() {
+        PackState::Loading(_) => None,
+        PackState::Ready(pack) => Some(pack.contains(path)),
+        PackState::Failed(_) => Some(false),
+    }
+}
+
+fn read_pack(state: &RefCell<PackState>, path: &str) -> Result<Vec<u8>, String> {
+    match &*state.borrow() {
+        PackState::Ready(pack) => pack.read(path),
+        PackState::Failed(err) => Err(err.clone()),
+        PackState::Loading(_) => Err("The asset pack is not loaded yet".to_string()),
+    }
+}
+
+/// Wait until the pack is loaded to read the file from it
+fn poll_pack(state: &RefCell<PackState>, path: &str) -> Poll<Vec<u8>, String> {
+    let loaded = match &mut *state.borrow_mut() {
+        PackState::Loading(fut) => match fut.poll() {
+            Ok(Async::NotReady) => return Ok(Async::NotReady),
+            Ok(Async::Ready(data)) => Some(Pack::from_bytes(data)),
+            Err(err) => Some(Err(err)),
+        },
+        _ => None,
+    };
+
+    if let Some(result) = loaded {
+        *state.borrow_mut() = match result {
+            Ok(pack) => PackState::Ready(pack),
+            Err(err) => PackState::Failed(err),
+        };
+    }
+
+    read_pack(state, path).map(Async::Ready)
+}
+
+/// Try each future until one of them succeed
+fn chain(candidates: Vec<FileFuture>, last: FileFuture) -> FileFuture {
+    candidates
+        .into_iter()
+        .rev()
+        .fold(last, |next, fut| Box::new(fut.or_else(move |_| next)))
+}
+
+/// Returns the path relative to the root if it's inside of it
+fn relative<'a>(root: &str, path: &'a str) -> Option<&'a str> {
+    if root.is_empty() {
+        return Some(path);
+    }
+
+    let rel = path.strip_prefix(root)?;
+    rel.strip_prefix('/')
+}
+
+/// Use forward slashes and remove the empty, `.` and `..` components
+fn normalize(path: &str) -> String {
+    let mut parts: Vec<&str> = vec![];
+    for part in path.split(|c| c == '/' || c == '\\') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            _ => parts.push(part),
+        }
+    }
+
+    parts.join("/")
+}
+
+/// Like `normalize` but keeps the root and the `..` components that leave the directory,
+/// two paths with the same key point to the same file
+pub(crate) fn normalize_key(path: &str) -> String {
+    let absolute = path.starts_with('/') || path.starts_with('\\');
+    let mut parts: Vec<&str> = vec![];
+    for part in path.split(|c| c == '/' || c == '\\') {
+        match part {
+            "" | "." => {}
+            ".." => match parts.last() {
+                Some(last) if *last != ".." => {
+                    parts.pop();
+                }
+                // the parent of the root is the root
+                _ if absolute => {}
+                _ => parts.push(part),
+            },
+            _ => parts.push(part),
+        }
+    }
+
+    let path = parts.join("/");
+    if absolute {
+        format!("/{}", path)
+    } else {
+        path
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::*;
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_normalize() {
+        assert_eq!(normalize("./sprites//hero.png"), "sprites/hero.png");
+        assert_eq!(normalize("/a/b/../c\\d.png"), "a/c/d.png");
+        assert_eq!(relative("sprites", "sprites/hero.png"), Some("hero.png"));
+        assert_eq!(relative("sprites", "sprites2/hero.png"), None);
+        assert_eq!(relative("", "hero.png"), Some("hero.png"));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_normalize_key() {
+        assert_eq!(normalize_key("./sprites//hero.png"), "sprites/hero.png");
+        assert_eq!(normalize_key("a/b/../../c.png"), "c.png");
+        assert_eq!(normalize_key("../a.png"), "../a.png");
+        assert_eq!(normalize_key("a/../../b\\c.png"), "../b/c.png");
+        assert_eq!(normalize_key("/x/a.png"), "/x/a.png");
+        assert_eq!(normalize_key("/../a.png"), "/a.png");
+        assert_ne!(normalize_key("../a.png"), normalize_key("a.png"));
+        assert_ne!(normalize_key("/x/a.png"), normalize_key("x/a.png"));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_mount_priority() {
+        let mut vfs = Vfs::new();
+        vfs.mount_embedded(
+            "",
+            vec![("hero.png", &b"base"[..]), ("bg.png", &b"bg"[..])],
+            0,
+        );
+        vfs.mount_embedded("", vec![("hero.png", &b"mod"[..])], 10);
+        vfs.mount_embedded("ui", vec![("button.png", &b"button"[..])], 0);
+
+        assert_eq!(vfs.roots(), vec!["", "ui", ""]);
+        assert_eq!(vfs.load("hero.png").wait(), Ok(b"mod".to_vec()));
+        assert_eq!(vfs.load("./bg.png").wait(), Ok(b"bg".to_vec()));
+        assert_eq!(vfs.load("ui/button.png").wait(), Ok(b"button".to_vec()));
+        assert!(vfs.contains("ui/button.png"));
+        assert!(!vfs.contains("button.png"));
+        assert!(vfs.load("missing.png").wait().is_err());
+
+        vfs.unmount("ui");
+        assert!(!vfs.contains("ui/button.png"));
+    }
+}
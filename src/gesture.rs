@@ -0,0 +1,540 @@
+use hashbrown::HashMap;
+use nae_core::Event;
+
+/// Direction of a swipe gesture
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SwipeDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// Gesture recognized from the touch events
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gesture {
+    Tap {
+        x: f32,
+        y: f32,
+    },
+    /// Dispatched instead of a second `Tap`
+    DoubleTap {
+        x: f32,
+        y: f32,
+    },
+    LongPress {
+        x: f32,
+        y: f32,
+    },
+    Swipe {
+        direction: SwipeDirection,
+        x: f32,
+        y: f32,
+        /// Pixels per second
+        velocity: f32,
+    },
+    /// Two fingers moving apart or together, the scale is relative to the last pinch
+    Pinch {
+        scale: f32,
+        x: f32,
+        y: f32,
+    },
+    /// Two fingers rotating, the angle in radians is relative to the last rotation
+    Rotate {
+        angle: f32,
+        x: f32,
+        y: f32,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TouchTrack {
+    start_x: f32,
+    start_y: f32,
+    x: f32,
+    y: f32,
+    start_time: f32,
+    moved: bool,
+}
+
+/// Recognize gestures using the touch events, the gestures detected are available during
+/// the frame they were detected
+pub struct GestureRecognizer {
+    /// Max time in seconds between the start and the end of a tap
+    pub tap_time: f32,
+    /// Max time in seconds between two taps to be a double tap
+    pub double_tap_time: f32,
+    /// Max distance in pixels the finger can move before it's not a tap or long press
+    pub tap_distance: f32,
+    /// Time in seconds to hold the finger to dispatch a long press
+    pub long_press_time: f32,
+    /// Min distance in pixels to be a swipe
+    pub swipe_distance: f32,
+    /// Max time in seconds to be a swipe
+    pub swipe_time: f32,
+
+    time: f32,
+    touches: HashMap<u64, TouchTrack>,
+    order: Vec<u64>,
+    multi_touch: bool,
+    long_pressed: bool,
+    last_tap: Option<(f32, f32, f32)>,
+    last_pair: Option<(f32, f32)>,
+    gestures: Vec<Gesture>,
+}
+
+impl GestureRecognizer {
+    pub(crate) fn new() -> Self {
+        Self {
+            tap_time: 0.3,
+            double_tap_time: 0.3,
+            tap_distance: 10.0,
+            long_press_time: 0.5,
+            swipe_distance: 50.0,
+            swipe_time: 0.5,
+            time: 0.0,
+            touches: HashMap::new(),
+            order: vec![],
+            multi_touch: false,
+            long_pressed: false,
+            last_tap: None,
+            last_pair: None,
+            gestures: vec![],
+        }
+    }
+
+    /// Gestures recognized on this frame
+    pub fn iter(&self) -> std::slice::Iter<Gesture> {
+        self.gestures.iter()
+    }
+
+    pub fn tap(&self) -> Option<(f32, f32)> {
+        self.gestures.iter().find_map(|g| match g {
+            Gesture::Tap { x, y } => Some((*x, *y)),
+            _ => None,
+        })
+    }
+
+    pub fn double_tap(&self) -> Option<(f32, f32)> {
+        self.gestures.iter().find_map(|g| match g {
+            Gesture::DoubleTap { x, y } => Some((*x, *y)),
+            _ => None,
+        })
+    }
+
+    pub fn long_press(&self) -> Option<(f32, f32)> {
+        self.gestures.iter().find_map(|g| match g {
+            Gesture::LongPress { x, y } => Some((*x, *y)),
+            _ => None,
+        })
+    }
+
+    pub fn swipe(&self) -> Option<SwipeDirection> {
+        self.gestures.iter().find_map(|g| match g {
+            Gesture::Swipe { direction, .. } => Some(*direction),
+            _ => None,
+        })
+    }
+
+    /// Scale of all the pinch gestures on this frame, 1.0 if there is none
+    pub fn pinch(&self) -> f32 {
+        self.gestures.iter().fold(1.0, |acc, g| match g {
+            Gesture::Pinch { scale, .. } => acc * scale,
+            _ => acc,
+        })
+    }
+
+    /// Angle of all the rotate gestures on this frame, 0.0 if there is none
+    pub fn rotation(&self) -> f32 {
+        self.gestures.iter().fold(0.0, |acc, g| match g {
+            Gesture::Rotate { angle, .. } => acc + angle,
+            _ => acc,
+        })
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.gestures.clear();
+    }
+
+    pub(crate) fn tick(&mut self, delta: f32) {
+        self.time += delta;
+
+        if self.multi_touch || self.long_pressed || self.order.len() != 1 {
+            return;
+        }
+
+        if let Some(t) = self.touches.get(&self.order[0]) {
+            if !t.moved && self.time - t.start_time >= self.long_press_time {
+                self.long_pressed = true;
+                self.gestures.push(Gesture::LongPress { x: t.x, y: t.y });
+            }
+        }
+    }
+
+    #[inline]
+    pub(crate) fn process(&mut self, evt: &Event) {
+        match evt {
+            Event::TouchStart { id, x, y } => {
+                self.touches.insert(
+                    *id,
+                    TouchTrack {
+                        start_x: *x,
+                        start_y: *y,
+                        x: *x,
+                        y: *y,
+                        start_time: self.time,
+                        moved: false,
+                    },
+                );
+                self.order.retain(|i| i != id);
+                self.order.push(*id);
+
+                if self.order.len() > 1 {
+                    self.multi_touch = true;
+                }
+                self.last_pair = self.pair();
+            }
+
+            Event::TouchMove { id, x, y } => {
+                let tap_distance = self.tap_distance;
+                if let Some(t) = self.touches.get_mut(id) {
+                    t.x = *x;
+                    t.y = *y;
+                    if distance(t.start_x, t.start_y, t.x, t.y) > tap_distance {
+                        t.moved = true;
+                    }
+                }
+
+                self.process_pair();
+            }
+
+            Event::TouchEnd { id, x, y } => {
+                if let Some(mut t) = self.remove(*id) {
+                    t.x = *x;
+                    t.y = *y;
+                    if !self.multi_touch && !self.long_pressed {
+                        self.process_end(&t);
+                    }
+                }
+                self.reset_if_empty();
+            }
+
+            Event::TouchCancel { id, .. } => {
+                self.remove(*id);
+                self.reset_if_empty();
+            }
+            _ => {}
+        }
+    }
+
+    fn remove(&mut self, id: u64) -> Option<TouchTrack> {
+        self.order.retain(|i| *i != id);
+        let touch = self.touches.remove(&id);
+        self.last_pair = self.pair();
+        touch
+    }
+
+    fn reset_if_empty(&mut self) {
+        if self.order.is_empty() {
+            self.multi_touch = false;
+            self.long_pressed = false;
+        }
+    }
+
+    fn process_end(&mut self, t: &TouchTrack) {
+        let elapsed = self.time - t.start_time;
+        let dist = distance(t.start_x, t.start_y, t.x, t.y);
+
+        if !t.moved && dist <= self.tap_distance && elapsed <= self.tap_time {
+            let double = match self.last_tap {
+                Some((time, x, y)) => {
+                    self.time - time <= self.double_tap_time
+                        && distance(x, y, t.x, t.y) <= self.tap_distance
+                }
+                _ => false,
+            };
+
+            if double {
+                self.last_tap = None;
+                self.gestures.push(Gesture::DoubleTap { x: t.x, y: t.y });
+            } else {
+                self.last_tap = Some((self.time, t.x, t.y));
+                self.gestures.push(Gesture::Tap { x: t.x, y: t.y });
+            }
+            return;
+        }
+
+        if dist >= self.swipe_distance && elapsed <= self.swipe_time {
+            let dx = t.x - t.start_x;
+            let dy = t.y - t.start_y;
+            let direction = if dx.abs() > dy.abs() {
+                if dx > 0.0 {
+                    SwipeDirection::Right
+                } else {
+                    SwipeDirection::Left
+                }
+            } else if dy > 0.0 {
+                SwipeDirection::Down
+            } else {
+                SwipeDirection::Up
+            };
+
+            self.gestures.push(Gesture::Swipe {
+                direction,
+                x: t.start_x,
+                y: t.start_y,
+                velocity: dist / elapsed.max(std::f32::EPSILON),
+            });
+        }
+    }
+
+    // distance and angle between the first two touches
+    fn pair(&self) -> Option<(f32, f32)> {
+        if self.order.len() < 2 {
+            return None;
+        }
+
+        let t1 = self.touches.get(&self.order[0])?;
+        let t2 = self.touches.get(&self.order[1])?;
+        let dist = distance(t1.x, t1.y, t2.x, t2.y);
+        let angle = (t2.y - t1.y).atan2(t2.x - t1.x);
+        Some((dist, angle))
+    }
+
+    fn process_pair(&mut self) {
+        let (last_dist, last_angle) = match self.last_pair {
+            Some(pair) => pair,
+            _ => return,
+        };
+
+        let (dist, angle) = match self.pair() {
+            Some(pair) => pair,
+            _ => return,
+        };
+
+        let (x, y) = {
+            let t1 = &self.touches[&self.order[0]];
+            let t2 = &self.touches[&self.order[1]];
+            ((t1.x + t2.x) * 0.5, (t1.y + t2.y) * 0.5)
+        };
+
+        if last_dist > 0.0 && dist != last_dist {
+            self.gestures.push(Gesture::Pinch {
+                scale: dist / last_dist,
+                x,
+                y,
+            });
+        }
+
+        let mut diff = angle - last_angle;
+        if diff > std::f32::consts::PI {
+            diff -= std::f32::consts::PI * 2.0;
+        } else if diff < -std::f32::consts::PI {
+            diff += std::f32::consts::PI * 2.0;
+        }
+
+        if diff != 0.0 {
+            self.gestures.push(Gesture::Rotate { angle: diff, x, y });
+        }
+
+        self.last_pair = Some((dist, angle));
+    }
+}
+
+fn distance(x1: f32, y1: f32, x2: f32, y2: f32) -> f32 {
+    let dx = x2 - x1;
+    let dy = y2 - y1;
+    (dx * dx + dy * dy).sqrt()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::*;
+
+    fn touch(recognizer: &mut GestureRecognizer, evt: Event, delta: f32) {
+        recognizer.clear();
+        recognizer.tick(delta);
+        recognizer.process(&evt);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_tap_and_double_tap() {
+        let mut g = GestureRecognizer::new();
+        touch(
+            &mut g,
+            Event::TouchStart {
+                id: 0,
+                x: 10.0,
+                y: 10.0,
+            },
+            0.0,
+        );
+        touch(
+            &mut g,
+            Event::TouchEnd {
+                id: 0,
+                x: 12.0,
+                y: 10.0,
+            },
+            0.1,
+        );
+        assert_eq!(g.tap(), Some((12.0, 10.0)));
+
+        touch(
+            &mut g,
+            Event::TouchStart {
+                id: 1,
+                x: 10.0,
+                y: 10.0,
+            },
+            0.1,
+        );
+        touch(
+            &mut g,
+            Event::TouchEnd {
+                id: 1,
+                x: 10.0,
+                y: 10.0,
+            },
+            0.1,
+        );
+        assert_eq!(g.tap(), None);
+        assert_eq!(g.double_tap(), Some((10.0, 10.0)));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_long_press_and_swipe() {
+        let mut g = GestureRecognizer::new();
+        touch(
+            &mut g,
+            Event::TouchStart {
+                id: 0,
+                x: 10.0,
+                y: 10.0,
+            },
+            0.0,
+        );
+        touch(
+            &mut g,
+            Event::TouchMove {
+                id: 0,
+                x: 11.0,
+                y: 10.0,
+            },
+            0.6,
+        );
+        assert_eq!(g.long_press(), Some((10.0, 10.0)));
+        touch(
+            &mut g,
+            Event::TouchEnd {
+                id: 0,
+                x: 11.0,
+                y: 10.0,
+            },
+            0.1,
+        );
+        assert_eq!(g.tap(), None);
+
+        touch(
+            &mut g,
+            Event::TouchStart {
+                id: 1,
+                x: 100.0,
+                y: 10.0,
+            },
+            0.0,
+        );
+        touch(
+            &mut g,
+            Event::TouchMove {
+                id: 1,
+                x: 50.0,
+                y: 12.0,
+            },
+            0.1,
+        );
+        touch(
+            &mut g,
+            Event::TouchEnd {
+                id: 1,
+                x: 20.0,
+                y: 15.0,
+            },
+            0.1,
+        );
+        assert_eq!(g.swipe(), Some(SwipeDirection::Left));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_pinch_and_rotate() {
+        let mut g = GestureRecognizer::new();
+        touch(
+            &mut g,
+            Event::TouchStart {
+                id: 0,
+                x: 0.0,
+                y: 0.0,
+            },
+            0.0,
+        );
+        touch(
+            &mut g,
+            Event::TouchStart {
+                id: 1,
+                x: 10.0,
+                y: 0.0,
+            },
+            0.0,
+        );
+        touch(
+            &mut g,
+            Event::TouchMove {
+                id: 1,
+                x: 20.0,
+                y: 0.0,
+            },
+            0.1,
+        );
+        assert_eq!(g.pinch(), 2.0);
+        assert_eq!(g.rotation(), 0.0);
+
+        touch(
+            &mut g,
+            Event::TouchMove {
+                id: 1,
+                x: 0.0,
+                y: 20.0,
+            },
+            0.1,
+        );
+        assert_eq!(g.pinch(), 1.0);
+        assert!((g.rotation() - std::f32::consts::FRAC_PI_2).abs() < 0.001);
+
+        //Multi touch sequences are not taps
+        touch(
+            &mut g,
+            Event::TouchEnd {
+                id: 1,
+                x: 0.0,
+                y: 20.0,
+            },
+            0.0,
+        );
+        touch(
+            &mut g,
+            Event::TouchEnd {
+                id: 0,
+                x: 0.0,
+                y: 0.0,
+            },
+            0.0,
+        );
+        assert_eq!(g.tap(), None);
+    }
+}
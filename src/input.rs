@@ -1,5 +1,5 @@
 use hashbrown::{HashMap, HashSet};
-use nae_core::{Event, KeyCode, MouseButton};
+use nae_core::{Event, GamepadAxis, GamepadButton, KeyCode, Modifiers, MouseButton};
 
 pub struct Mouse {
     pub x: f32,
@@ -7,6 +7,19 @@ pub struct Mouse {
     pub pressed: HashSet<MouseButton>,
     pub down: HashMap<MouseButton, f32>,
     pub released: HashSet<MouseButton>,
+
+    /// Relative motion on this frame, it's reported also when the pointer is locked
+    pub motion_x: f32,
+    pub motion_y: f32,
+
+    /// Wheel delta accumulated on this frame
+    pub wheel_delta_x: f32,
+    pub wheel_delta_y: f32,
+
+    /// Distance the mouse must move with a button down to start a drag
+    pub drag_threshold: f32,
+    drag_start: HashMap<MouseButton, (f32, f32)>,
+    dragging: HashSet<MouseButton>,
 }
 
 impl Mouse {
@@ -17,6 +30,13 @@ impl Mouse {
             pressed: HashSet::new(),
             down: HashMap::new(),
             released: HashSet::new(),
+            motion_x: 0.0,
+            motion_y: 0.0,
+            wheel_delta_x: 0.0,
+            wheel_delta_y: 0.0,
+            drag_threshold: 4.0,
+            drag_start: HashMap::new(),
+            dragging: HashSet::new(),
         }
     }
 
@@ -40,48 +60,111 @@ impl Mouse {
         self.pressed.contains(&btn)
     }
 
+    /// Relative motion on this frame
+    pub fn motion(&self) -> (f32, f32) {
+        (self.motion_x, self.motion_y)
+    }
+
+    /// Wheel delta accumulated on this frame
+    pub fn wheel_delta(&self) -> (f32, f32) {
+        (self.wheel_delta_x, self.wheel_delta_y)
+    }
+
+    /// Position where the button was pressed if it's still down
+    pub fn drag_start(&self, btn: MouseButton) -> Option<(f32, f32)> {
+        self.drag_start.get(&btn).cloned()
+    }
+
+    /// Returns true if the button is down and the mouse moved more than the drag threshold
+    pub fn is_dragging(&self, btn: MouseButton) -> bool {
+        self.dragging.contains(&btn)
+    }
+
+    /// Offset from the drag start to the current position
+    pub fn drag_offset(&self, btn: MouseButton) -> (f32, f32) {
+        self.drag_start(btn)
+            .map_or((0.0, 0.0), |(x, y)| (self.x - x, self.y - y))
+    }
+
+    pub fn drag_distance(&self, btn: MouseButton) -> f32 {
+        let (x, y) = self.drag_offset(btn);
+        (x * x + y * y).sqrt()
+    }
+
     pub(crate) fn clear(&mut self) {
         self.pressed.clear();
         self.released.clear();
+        self.motion_x = 0.0;
+        self.motion_y = 0.0;
+        self.wheel_delta_x = 0.0;
+        self.wheel_delta_y = 0.0;
+    }
+
+    pub(crate) fn tick(&mut self, delta: f32) {
+        self.down.values_mut().for_each(|t| *t += delta);
     }
 
     #[inline]
-    pub(crate) fn process(&mut self, evt: &Event, delta: f32) {
+    pub(crate) fn process(&mut self, evt: &Event) {
         match evt {
             Event::MouseMove { x, y } => {
                 self.x = *x as f32;
                 self.y = *y as f32;
+                self.update_drag();
+            }
+
+            Event::MouseMotion { delta_x, delta_y } => {
+                self.motion_x += *delta_x;
+                self.motion_y += *delta_y;
+            }
+
+            Event::MouseWheel { delta_x, delta_y } => {
+                self.wheel_delta_x += *delta_x;
+                self.wheel_delta_y += *delta_y;
             }
 
-            Event::MouseUp { x, y, button } => {
+            Event::MouseUp { x, y, button, .. } => {
                 self.x = *x as f32;
                 self.y = *y as f32;
 
                 self.down.remove(button);
                 self.pressed.remove(button);
                 self.released.insert(*button);
+                self.drag_start.remove(button);
+                self.dragging.remove(button);
             }
 
-            Event::MouseDown { x, y, button } => {
+            Event::MouseDown { x, y, button, .. } => {
                 self.x = *x as f32;
                 self.y = *y as f32;
 
-                if let Some(t) = self.down.get_mut(button) {
-                    *t += delta;
-                } else {
+                if !self.down.contains_key(button) {
                     self.down.insert(*button, 0.0);
                     self.pressed.insert(*button);
+                    self.drag_start.insert(*button, (self.x, self.y));
                 }
             }
             _ => {}
         }
     }
+
+    fn update_drag(&mut self) {
+        let (x, y) = (self.x, self.y);
+        let threshold = self.drag_threshold * self.drag_threshold;
+        for (btn, (start_x, start_y)) in self.drag_start.iter() {
+            let (dx, dy) = (x - start_x, y - start_y);
+            if dx * dx + dy * dy >= threshold {
+                self.dragging.insert(*btn);
+            }
+        }
+    }
 }
 
 pub struct Keyboard {
     pub pressed: HashSet<KeyCode>,
     pub down: HashMap<KeyCode, f32>,
     pub released: HashSet<KeyCode>,
+    modifiers: Modifiers,
 }
 
 impl Keyboard {
@@ -90,9 +173,15 @@ impl Keyboard {
             pressed: HashSet::new(),
             down: HashMap::new(),
             released: HashSet::new(),
+            modifiers: Modifiers::empty(),
         }
     }
 
+    /// Modifiers down on the last keyboard or mouse event
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
     pub fn was_released(&self, key: KeyCode) -> bool {
         self.released.contains(&key)
     }
@@ -114,24 +203,425 @@ impl Keyboard {
         self.released.clear();
     }
 
+    // the OS auto-repeat rate is not the frame rate so the time is increased each frame
+    pub(crate) fn tick(&mut self, delta: f32) {
+        self.down.values_mut().for_each(|t| *t += delta);
+    }
+
     #[inline]
-    pub(crate) fn process(&mut self, evt: &Event, delta: f32) {
+    pub(crate) fn process(&mut self, evt: &Event) {
         match evt {
-            Event::KeyUp { key } => {
+            Event::KeyUp { key, modifiers } => {
+                self.modifiers = *modifiers;
                 self.down.remove(key);
                 self.pressed.remove(key);
                 self.released.insert(*key);
             }
 
-            Event::KeyDown { key } => {
-                if let Some(t) = self.down.get_mut(key) {
-                    *t += delta;
-                } else {
+            Event::KeyDown { key, modifiers, .. } => {
+                self.modifiers = *modifiers;
+                if !self.down.contains_key(key) {
                     self.down.insert(*key, 0.0);
                     self.pressed.insert(*key);
                 }
             }
+
+            Event::MouseDown { modifiers, .. } | Event::MouseUp { modifiers, .. } => {
+                self.modifiers = *modifiers;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// State of a connected gamepad
+pub struct Gamepad {
+    pub id: u32,
+    pub pressed: HashSet<GamepadButton>,
+    pub down: HashMap<GamepadButton, f32>,
+    pub released: HashSet<GamepadButton>,
+    pub axes: HashMap<GamepadAxis, f32>,
+
+    /// Axis values below this are read as 0.0
+    pub deadzone: f32,
+}
+
+impl Gamepad {
+    fn new(id: u32, deadzone: f32) -> Self {
+        Self {
+            id,
+            pressed: HashSet::new(),
+            down: HashMap::new(),
+            released: HashSet::new(),
+            axes: HashMap::new(),
+            deadzone,
+        }
+    }
+
+    pub fn was_released(&self, btn: GamepadButton) -> bool {
+        self.released.contains(&btn)
+    }
+
+    pub fn is_down(&self, btn: GamepadButton) -> bool {
+        self.down.contains_key(&btn)
+    }
+
+    pub fn down_delta(&self, btn: GamepadButton) -> f32 {
+        *self.down.get(&btn).unwrap_or(&0.0)
+    }
+
+    pub fn was_pressed(&self, btn: GamepadButton) -> bool {
+        self.pressed.contains(&btn)
+    }
+
+    /// Value of the axis with the deadzone applied, rescaled to keep the full range
+    pub fn axis(&self, axis: GamepadAxis) -> f32 {
+        let value = *self.axes.get(&axis).unwrap_or(&0.0);
+        let abs = value.abs();
+        if abs <= self.deadzone {
+            return 0.0;
+        }
+
+        let range = 1.0 - self.deadzone;
+        if range <= 0.0 {
+            return value.signum();
+        }
+
+        value.signum() * ((abs - self.deadzone) / range).min(1.0)
+    }
+
+    /// Value of the axis without the deadzone
+    pub fn raw_axis(&self, axis: GamepadAxis) -> f32 {
+        *self.axes.get(&axis).unwrap_or(&0.0)
+    }
+
+    /// Left stick as (x, y)
+    pub fn left_stick(&self) -> (f32, f32) {
+        (self.axis(GamepadAxis::LeftX), self.axis(GamepadAxis::LeftY))
+    }
+
+    /// Right stick as (x, y)
+    pub fn right_stick(&self) -> (f32, f32) {
+        (
+            self.axis(GamepadAxis::RightX),
+            self.axis(GamepadAxis::RightY),
+        )
+    }
+}
+
+/// Connected gamepads sorted by the order they were connected
+pub struct Gamepads {
+    pads: Vec<Gamepad>,
+    deadzone: f32,
+}
+
+impl Gamepads {
+    pub(crate) fn new() -> Self {
+        Self {
+            pads: vec![],
+            deadzone: 0.15,
+        }
+    }
+
+    pub fn get(&self, id: u32) -> Option<&Gamepad> {
+        self.pads.iter().find(|p| p.id == id)
+    }
+
+    /// First connected gamepad, useful for single player games
+    pub fn first(&self) -> Option<&Gamepad> {
+        self.pads.first()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<Gamepad> {
+        self.pads.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pads.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pads.is_empty()
+    }
+
+    pub fn was_released(&self, id: u32, btn: GamepadButton) -> bool {
+        self.get(id).map_or(false, |p| p.was_released(btn))
+    }
+
+    pub fn is_down(&self, id: u32, btn: GamepadButton) -> bool {
+        self.get(id).map_or(false, |p| p.is_down(btn))
+    }
+
+    pub fn down_delta(&self, id: u32, btn: GamepadButton) -> f32 {
+        self.get(id).map_or(0.0, |p| p.down_delta(btn))
+    }
+
+    pub fn was_pressed(&self, id: u32, btn: GamepadButton) -> bool {
+        self.get(id).map_or(false, |p| p.was_pressed(btn))
+    }
+
+    pub fn axis(&self, id: u32, axis: GamepadAxis) -> f32 {
+        self.get(id).map_or(0.0, |p| p.axis(axis))
+    }
+
+    pub fn deadzone(&self) -> f32 {
+        self.deadzone
+    }
+
+    /// Set the deadzone (0.0 to 1.0) of the connected gamepads and the new ones
+    pub fn set_deadzone(&mut self, deadzone: f32) {
+        self.deadzone = deadzone.max(0.0).min(1.0);
+        let deadzone = self.deadzone;
+        self.pads.iter_mut().for_each(|p| p.deadzone = deadzone);
+    }
+
+    fn get_or_insert(&mut self, id: u32) -> &mut Gamepad {
+        let index = match self.pads.iter().position(|p| p.id == id) {
+            Some(index) => index,
+            _ => {
+                self.pads.push(Gamepad::new(id, self.deadzone));
+                self.pads.len() - 1
+            }
+        };
+
+        &mut self.pads[index]
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.pads.iter_mut().for_each(|p| {
+            p.pressed.clear();
+            p.released.clear();
+        });
+    }
+
+    // gamepads don't repeat the button events so the time is increased each frame
+    pub(crate) fn tick(&mut self, delta: f32) {
+        self.pads.iter_mut().for_each(|p| {
+            p.down.values_mut().for_each(|t| *t += delta);
+        });
+    }
+
+    #[inline]
+    pub(crate) fn process(&mut self, evt: &Event) {
+        match evt {
+            Event::GamepadConnected { id } => {
+                self.get_or_insert(*id);
+            }
+
+            Event::GamepadDisconnected { id } => {
+                self.pads.retain(|p| p.id != *id);
+            }
+
+            Event::GamepadButtonUp { id, button } => {
+                let pad = self.get_or_insert(*id);
+                pad.down.remove(button);
+                pad.pressed.remove(button);
+                pad.released.insert(*button);
+            }
+
+            Event::GamepadButtonDown { id, button } => {
+                let pad = self.get_or_insert(*id);
+                if !pad.down.contains_key(button) {
+                    pad.down.insert(*button, 0.0);
+                    pad.pressed.insert(*button);
+                }
+            }
+
+            Event::GamepadAxisMotion { id, axis, value } => {
+                self.get_or_insert(*id).axes.insert(*axis, *value);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Position of a finger on the screen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Touch {
+    pub id: u64,
+    pub x: f32,
+    pub y: f32,
+    pub start_x: f32,
+    pub start_y: f32,
+    pub down: f32,
+}
+
+/// Touches on the screen sorted by the order they started
+pub struct Touches {
+    touches: Vec<Touch>,
+    pub started: HashSet<u64>,
+    pub ended: HashMap<u64, Touch>,
+}
+
+impl Touches {
+    pub(crate) fn new() -> Self {
+        Self {
+            touches: vec![],
+            started: HashSet::new(),
+            ended: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, id: u64) -> Option<&Touch> {
+        self.touches.iter().find(|t| t.id == id)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<Touch> {
+        self.touches.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.touches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.touches.is_empty()
+    }
+
+    pub fn was_started(&self, id: u64) -> bool {
+        self.started.contains(&id)
+    }
+
+    pub fn was_ended(&self, id: u64) -> bool {
+        self.ended.contains_key(&id)
+    }
+
+    pub fn is_down(&self, id: u64) -> bool {
+        self.get(id).is_some()
+    }
+
+    pub fn down_delta(&self, id: u64) -> f32 {
+        self.get(id).map_or(0.0, |t| t.down)
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.started.clear();
+        self.ended.clear();
+    }
+
+    pub(crate) fn tick(&mut self, delta: f32) {
+        self.touches.iter_mut().for_each(|t| t.down += delta);
+    }
+
+    #[inline]
+    pub(crate) fn process(&mut self, evt: &Event) {
+        match evt {
+            Event::TouchStart { id, x, y } => {
+                self.touches.retain(|t| t.id != *id);
+                self.touches.push(Touch {
+                    id: *id,
+                    x: *x,
+                    y: *y,
+                    start_x: *x,
+                    start_y: *y,
+                    down: 0.0,
+                });
+                self.started.insert(*id);
+            }
+
+            Event::TouchMove { id, x, y } => {
+                if let Some(t) = self.touches.iter_mut().find(|t| t.id == *id) {
+                    t.x = *x;
+                    t.y = *y;
+                }
+            }
+
+            Event::TouchEnd { id, x, y } | Event::TouchCancel { id, x, y } => {
+                if let Some(index) = self.touches.iter().position(|t| t.id == *id) {
+                    let mut touch = self.touches.remove(index);
+                    touch.x = *x;
+                    touch.y = *y;
+                    self.started.remove(id);
+                    self.ended.insert(*id, touch);
+                }
+            }
             _ => {}
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::*;
+
+    fn axis_motion(gamepads: &mut Gamepads, id: u32, axis: GamepadAxis, value: f32) {
+        gamepads.process(&Event::GamepadAxisMotion { id, axis, value });
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_gamepad_deadzone() {
+        let mut gamepads = Gamepads::new();
+        gamepads.set_deadzone(0.2);
+        axis_motion(&mut gamepads, 0, GamepadAxis::LeftX, 0.1);
+        axis_motion(&mut gamepads, 0, GamepadAxis::LeftY, -0.6);
+        axis_motion(&mut gamepads, 0, GamepadAxis::RightX, 1.0);
+
+        let pad = gamepads.get(0).unwrap();
+        assert_eq!(pad.axis(GamepadAxis::LeftX), 0.0);
+        assert_eq!(pad.raw_axis(GamepadAxis::LeftX), 0.1);
+        assert!((pad.axis(GamepadAxis::LeftY) + 0.5).abs() < 0.0001);
+        assert_eq!(pad.axis(GamepadAxis::RightX), 1.0);
+        assert_eq!(pad.right_stick(), (1.0, 0.0));
+
+        // the deadzone is applied to the gamepads already connected
+        gamepads.set_deadzone(0.7);
+        assert_eq!(gamepads.axis(0, GamepadAxis::LeftY), 0.0);
+        gamepads.set_deadzone(2.0);
+        assert_eq!(gamepads.deadzone(), 1.0);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_gamepad_connection() {
+        let mut gamepads = Gamepads::new();
+        assert!(gamepads.is_empty());
+
+        gamepads.process(&Event::GamepadConnected { id: 3 });
+        gamepads.process(&Event::GamepadConnected { id: 1 });
+        gamepads.process(&Event::GamepadConnected { id: 3 });
+        assert_eq!(gamepads.len(), 2);
+        assert_eq!(gamepads.first().map(|p| p.id), Some(3));
+
+        gamepads.process(&Event::GamepadDisconnected { id: 3 });
+        assert_eq!(gamepads.len(), 1);
+        assert!(gamepads.get(3).is_none());
+        assert_eq!(gamepads.first().map(|p| p.id), Some(1));
+        assert_eq!(gamepads.axis(3, GamepadAxis::LeftX), 0.0);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_gamepad_buttons() {
+        let mut gamepads = Gamepads::new();
+        let down = Event::GamepadButtonDown {
+            id: 0,
+            button: GamepadButton::South,
+        };
+
+        gamepads.process(&down);
+        assert!(gamepads.was_pressed(0, GamepadButton::South));
+        assert!(gamepads.is_down(0, GamepadButton::South));
+        assert!(!gamepads.was_pressed(1, GamepadButton::South));
+
+        gamepads.clear();
+        gamepads.tick(0.5);
+        gamepads.process(&down);
+        assert!(!gamepads.was_pressed(0, GamepadButton::South));
+        assert_eq!(gamepads.down_delta(0, GamepadButton::South), 0.5);
+
+        gamepads.tick(0.25);
+        assert_eq!(gamepads.down_delta(0, GamepadButton::South), 0.75);
+
+        gamepads.process(&Event::GamepadButtonUp {
+            id: 0,
+            button: GamepadButton::South,
+        });
+        assert!(gamepads.was_released(0, GamepadButton::South));
+        assert!(!gamepads.is_down(0, GamepadButton::South));
+        assert_eq!(gamepads.down_delta(0, GamepadButton::South), 0.0);
+    }
+}
@@ -1,32 +1,41 @@
+mod action;
 mod app;
+mod file;
+mod gesture;
 mod input;
 pub mod m2d;
+mod plugin;
+mod profiler;
 mod random;
+mod replay;
 mod res;
+mod scene;
+mod sequence;
 pub mod tween;
 
 pub use nae_derive::main;
 
-/*
-  TODO think about a plugin trait?
-    something like .plugins(vec![Tween])
-    and:
-        trait Plugin {
-            pre_start_hook: Option<fn(&mut App, &mut State, plugin: P) -> Result<(), String>>,
-            and post_start, pre_update, post_update, etc...
-        }
-    ----
-    this allow to alter the event cycle without change the original code
-*/
-
 pub use app::{init, init_with, App};
+pub use plugin::Plugin;
 
 pub mod prelude {
+    pub use super::action::{ActionMap, AxisBinding, Binding};
     pub use super::app::*;
+    pub use super::embed_files;
+    pub use super::file::{FileFuture, Vfs};
+    pub use super::gesture::{Gesture, SwipeDirection};
     pub use super::m2d;
+    pub use super::plugin::*;
+    pub use super::profiler::{FrameStats, Profiler, ProfilerOverlay};
     pub use super::random::*;
+    pub use super::replay::{InputRecord, RecordedFrame};
     pub use super::res::*;
+    pub use super::scene::*;
+    pub use super::sequence::{BufferedInput, InputBuffer, InputButton, Sequence};
     pub use super::tween;
     pub use backend::*;
+    pub use nae_core::window::{CursorIcon, RgbaImage};
     pub use nae_core::*;
+    pub use nae_derive::Resource;
+    pub use nae_pack::{Pack, PackBuilder, PackEntry};
 }
@@ -1,6 +1,6 @@
 use crate::res::{Resource, ResourceParser};
 use crate::{resource_parser, App};
-use backend::{BaseApp, BaseSystem, Texture};
+use backend::{BaseApp, BaseSystem, ResourceState, Texture};
 use hashbrown::HashMap;
 use nae_gfx::Draw;
 use serde::{Deserialize, Serialize};
@@ -19,6 +19,7 @@ pub struct TextureAtlas {
     root: String,
     inner: Rc<RefCell<Option<InnerAtlas>>>,
     textures: Rc<RefCell<HashMap<String, Texture>>>,
+    error: Rc<RefCell<Option<String>>>,
 }
 
 impl TextureAtlas {
@@ -32,6 +33,18 @@ impl TextureAtlas {
             _ => false,
         }
     }
+
+    /// Returns the loading state of the atlas and its texture
+    pub fn state(&self) -> ResourceState {
+        if let Some(err) = &*self.error.borrow() {
+            return ResourceState::Failed(err.clone());
+        }
+
+        match &*self.inner.borrow() {
+            Some(inner) => inner.tex.state(),
+            _ => ResourceState::Pending,
+        }
+    }
 }
 
 impl ResourceParser for TextureAtlas {
@@ -40,9 +53,29 @@ impl ResourceParser for TextureAtlas {
     fn parse_resource(&mut self, app: &mut Self::App, data: Vec<u8>) -> Result<(), String> {
         let data: AtlasRoot = serde_json::from_slice(&data).map_err(|e| e.to_string())?;
         let path = Path::new(&self.root).join(&data.meta.image);
-        let tex: Texture = app.load_resource(&path.display().to_string())?;
+
+        // when reloaded the texture is kept if the image is the same, it's reloaded on its own
+        let (current, previous) = match &*self.inner.borrow() {
+            Some(inner) if inner.data.meta.image == data.meta.image => {
+                (Some(inner.tex.clone()), None)
+            }
+            Some(inner) => (
+                None,
+                Some(Path::new(&self.root).join(&inner.data.meta.image)),
+            ),
+            None => (None, None),
+        };
+        let tex: Texture = match current {
+            Some(tex) => tex,
+            None => app.load_resource(&path.display().to_string())?,
+        };
+
+        if let Some(previous) = previous {
+            app.unload_dependency::<Texture>(&previous.display().to_string());
+        }
 
         let mut textures = self.textures.borrow_mut();
+        textures.clear();
         for frame in &data.frames {
             textures.insert(
                 frame.filename.to_string(),
@@ -59,6 +92,10 @@ impl ResourceParser for TextureAtlas {
 
         Ok(())
     }
+
+    fn set_error(&mut self, err: String) {
+        *self.error.borrow_mut() = Some(err);
+    }
 }
 
 impl<T, S> Resource<T> for TextureAtlas
@@ -73,6 +110,7 @@ where
             inner: Rc::new(RefCell::new(None)),
             root: root,
             textures: Rc::new(RefCell::new(HashMap::new())),
+            error: Rc::new(RefCell::new(None)),
         })
     }
 
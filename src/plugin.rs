@@ -0,0 +1,105 @@
+use crate::app::App;
+use nae_core::Event;
+use std::any::Any;
+
+/// Allow to downcast plugins to their concrete type
+pub trait AsAny {
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Hooks into the app's lifecycle without changing the user's callbacks
+pub trait Plugin: AsAny {
+    /// Called before the start callback
+    fn pre_start(&mut self, _app: &mut App) {}
+
+    /// Called after the start callback
+    fn post_start(&mut self, _app: &mut App) {}
+
+    /// Called before each update callback
+    fn pre_update(&mut self, _app: &mut App) {}
+
+    /// Called after each update callback
+    fn post_update(&mut self, _app: &mut App) {}
+
+    /// Called before the draw callback
+    fn pre_draw(&mut self, _app: &mut App) {}
+
+    /// Called after the draw callback
+    fn post_draw(&mut self, _app: &mut App) {}
+
+    /// Called with each event before the input state and the event callback.
+    /// Returning `None` will consume the event. New events can be injected pushing them to
+    /// `app.system().events()`, they will be processed after the current one
+    fn event(&mut self, _app: &mut App, event: Event) -> Option<Event> {
+        Some(event)
+    }
+}
+
+/// List of plugins in the order they were added
+pub(crate) struct Plugins(Vec<Box<dyn Plugin>>);
+
+impl Plugins {
+    pub fn new() -> Self {
+        Self(vec![])
+    }
+
+    pub fn push(&mut self, plugin: Box<dyn Plugin>) {
+        self.0.push(plugin);
+    }
+
+    pub fn get_mut<T: Plugin + 'static>(&mut self) -> Option<&mut T> {
+        self.0
+            .iter_mut()
+            .find_map(|p| (**p).as_any_mut().downcast_mut::<T>())
+    }
+
+    pub fn pre_start(app: &mut App) {
+        with_plugins(app, |p, app| p.pre_start(app));
+    }
+
+    pub fn post_start(app: &mut App) {
+        with_plugins(app, |p, app| p.post_start(app));
+    }
+
+    pub fn pre_update(app: &mut App) {
+        with_plugins(app, |p, app| p.pre_update(app));
+    }
+
+    pub fn post_update(app: &mut App) {
+        with_plugins(app, |p, app| p.post_update(app));
+    }
+
+    pub fn pre_draw(app: &mut App) {
+        with_plugins(app, |p, app| p.pre_draw(app));
+    }
+
+    pub fn post_draw(app: &mut App) {
+        with_plugins(app, |p, app| p.post_draw(app));
+    }
+
+    pub fn event(app: &mut App, event: Event) -> Option<Event> {
+        let mut event = Some(event);
+        with_plugins(app, |p, app| {
+            if let Some(evt) = event.take() {
+                event = p.event(app, evt);
+            }
+        });
+        event
+    }
+}
+
+// Plugins are moved out of the app while the hooks run to allow them to borrow the app
+fn with_plugins<F>(app: &mut App, mut cb: F)
+where
+    F: FnMut(&mut dyn Plugin, &mut App),
+{
+    let mut plugins = std::mem::replace(&mut app.plugins, Plugins::new());
+    plugins.0.iter_mut().for_each(|p| cb(&mut **p, app));
+    app.plugins = plugins;
+}
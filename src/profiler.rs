@@ -0,0 +1,315 @@
+use crate::app::App;
+use crate::plugin::Plugin;
+use backend::{Draw, Font, GfxStats};
+use nae_core::{log, performance_now, Color, Event, KeyCode};
+use std::collections::VecDeque;
+
+/// Milliseconds spent on each phase of a frame and the graphics counters
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameStats {
+    /// Time since the start of the previous frame
+    pub frame: f32,
+    pub events: f32,
+    pub resources: f32,
+    pub update: f32,
+    pub draw: f32,
+    /// Time between the end of the previous draw and this frame, swapping buffers
+    /// and waiting for the vsync or the fps target
+    pub swap: f32,
+    pub gfx: GfxStats,
+}
+
+/// Phase of the frame measured by the profiler
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Phase {
+    Events,
+    Resources,
+    Update,
+    Draw,
+}
+
+/// Measure the time spent on each frame, the last frames are kept on a history
+#[derive(Clone)]
+pub struct Profiler {
+    /// Max number of frames kept
+    pub capacity: usize,
+
+    history: VecDeque<FrameStats>,
+    current: FrameStats,
+    frame_start: Option<f64>,
+    draw_end: Option<f64>,
+    mark: f64,
+    excluded: GfxStats,
+}
+
+impl Profiler {
+    pub(crate) fn new() -> Self {
+        Self {
+            capacity: 120,
+            history: VecDeque::new(),
+            current: Default::default(),
+            frame_start: None,
+            draw_end: None,
+            mark: performance_now(),
+            excluded: Default::default(),
+        }
+    }
+
+    /// Stats of the last frame completed
+    pub fn last(&self) -> Option<&FrameStats> {
+        self.history.back()
+    }
+
+    /// Frames from the oldest to the newest
+    pub fn iter(&self) -> impl Iterator<Item = &FrameStats> {
+        self.history.iter()
+    }
+
+    /// Average of the frames on the history
+    pub fn average(&self) -> FrameStats {
+        let len = self.history.len().max(1);
+        let mut avg = self
+            .history
+            .iter()
+            .fold(FrameStats::default(), |acc, f| FrameStats {
+                frame: acc.frame + f.frame,
+                events: acc.events + f.events,
+                resources: acc.resources + f.resources,
+                update: acc.update + f.update,
+                draw: acc.draw + f.draw,
+                swap: acc.swap + f.swap,
+                gfx: add_gfx(&acc.gfx, &f.gfx),
+            });
+
+        let n = len as f32;
+        avg.frame /= n;
+        avg.events /= n;
+        avg.resources /= n;
+        avg.update /= n;
+        avg.draw /= n;
+        avg.swap /= n;
+
+        let n = len as u32;
+        avg.gfx.draw_calls /= n;
+        avg.gfx.vertices /= n;
+        avg.gfx.texture_binds /= n;
+        avg.gfx.color_flushes /= n;
+        avg.gfx.image_flushes /= n;
+        avg.gfx.pattern_flushes /= n;
+        avg.gfx.text_flushes /= n;
+        avg
+    }
+
+    pub(crate) fn begin_frame(&mut self) {
+        let now = performance_now();
+        self.current = Default::default();
+        if let Some(start) = self.frame_start {
+            self.current.frame = (now - start) as f32;
+        }
+        if let Some(end) = self.draw_end {
+            self.current.swap = (now - end) as f32;
+        }
+        self.frame_start = Some(now);
+        self.mark = now;
+    }
+
+    /// Start measuring a phase, used to skip the time spent between phases
+    pub(crate) fn mark(&mut self) {
+        self.mark = performance_now();
+    }
+
+    /// Add the time since the last mark to the phase
+    pub(crate) fn measure(&mut self, phase: Phase) {
+        let now = performance_now();
+        let elapsed = (now - self.mark) as f32;
+        self.mark = now;
+
+        match phase {
+            Phase::Events => self.current.events += elapsed,
+            Phase::Resources => self.current.resources += elapsed,
+            Phase::Update => self.current.update += elapsed,
+            Phase::Draw => self.current.draw += elapsed,
+        }
+    }
+
+    /// Counters added by the profiler's overlay are not part of the frame's stats
+    pub(crate) fn exclude(&mut self, stats: GfxStats) {
+        self.excluded = add_gfx(&self.excluded, &stats);
+    }
+
+    pub(crate) fn end_frame(&mut self, gfx: GfxStats) {
+        self.draw_end = Some(performance_now());
+        self.current.gfx = sub_gfx(&gfx, &self.excluded);
+        self.excluded = Default::default();
+
+        self.history.push_back(self.current);
+        while self.history.len() > self.capacity {
+            self.history.pop_front();
+        }
+    }
+
+    /// Draw a graph of the frame times with the average stats as text
+    pub fn draw_overlay(&self, draw: &mut Draw, font: &Font, x: f32, y: f32) {
+        const GRAPH_HEIGHT: f32 = 80.0;
+        const BAR_WIDTH: f32 = 2.0;
+        // 33ms (30fps) fills the graph
+        let scale = GRAPH_HEIGHT / 33.3;
+
+        let width = self.capacity as f32 * BAR_WIDTH;
+        let color = draw.color;
+        let alpha = draw.alpha;
+
+        draw.alpha = 0.7;
+        draw.color = Color::BLACK;
+        draw.rect(x, y, width, GRAPH_HEIGHT + 110.0);
+        draw.alpha = 1.0;
+
+        let offset = self.capacity.saturating_sub(self.history.len()) as f32 * BAR_WIDTH;
+        for (i, frame) in self.history.iter().enumerate() {
+            let bar_x = x + offset + i as f32 * BAR_WIDTH;
+            let mut bar_y = y + GRAPH_HEIGHT;
+            let phases = [
+                (frame.events, Color::GREEN),
+                (frame.resources, Color::ORANGE),
+                (frame.update, Color::BLUE),
+                (frame.draw, Color::RED),
+                (frame.swap, Color::GRAY),
+            ];
+
+            for (time, phase_color) in phases.iter() {
+                let height = (time * scale).min(bar_y - y);
+                bar_y -= height;
+                draw.color = *phase_color;
+                draw.rect(bar_x, bar_y, BAR_WIDTH, height);
+            }
+        }
+
+        // 60fps line
+        draw.color = Color::WHITE;
+        draw.line(
+            x,
+            y + GRAPH_HEIGHT - 16.6 * scale,
+            x + width,
+            y + GRAPH_HEIGHT - 16.6 * scale,
+            1.0,
+        );
+
+        let avg = self.average();
+        let text = format!(
+            "frame {:.2}ms\nevents {:.2} resources {:.2}\nupdate {:.2} draw {:.2} swap {:.2}\ndraw calls {} vertices {} textures {}\nflushes color {} image {} pattern {} text {}",
+            avg.frame,
+            avg.events,
+            avg.resources,
+            avg.update,
+            avg.draw,
+            avg.swap,
+            avg.gfx.draw_calls,
+            avg.gfx.vertices,
+            avg.gfx.texture_binds,
+            avg.gfx.color_flushes,
+            avg.gfx.image_flushes,
+            avg.gfx.pattern_flushes,
+            avg.gfx.text_flushes,
+        );
+        draw.text(font, &text, x + 4.0, y + GRAPH_HEIGHT + 4.0, 14.0);
+
+        draw.color = color;
+        draw.alpha = alpha;
+    }
+}
+
+fn add_gfx(a: &GfxStats, b: &GfxStats) -> GfxStats {
+    GfxStats {
+        draw_calls: a.draw_calls + b.draw_calls,
+        vertices: a.vertices + b.vertices,
+        texture_binds: a.texture_binds + b.texture_binds,
+        color_flushes: a.color_flushes + b.color_flushes,
+        image_flushes: a.image_flushes + b.image_flushes,
+        pattern_flushes: a.pattern_flushes + b.pattern_flushes,
+        text_flushes: a.text_flushes + b.text_flushes,
+    }
+}
+
+fn sub_gfx(a: &GfxStats, b: &GfxStats) -> GfxStats {
+    GfxStats {
+        draw_calls: a.draw_calls.saturating_sub(b.draw_calls),
+        vertices: a.vertices.saturating_sub(b.vertices),
+        texture_binds: a.texture_binds.saturating_sub(b.texture_binds),
+        color_flushes: a.color_flushes.saturating_sub(b.color_flushes),
+        image_flushes: a.image_flushes.saturating_sub(b.image_flushes),
+        pattern_flushes: a.pattern_flushes.saturating_sub(b.pattern_flushes),
+        text_flushes: a.text_flushes.saturating_sub(b.text_flushes),
+    }
+}
+
+/// Plugin that draws the profiler's overlay over the app, the key given toggles it
+pub struct ProfilerOverlay {
+    font_data: &'static [u8],
+    font: Option<Font>,
+    pub visible: bool,
+    pub toggle_key: Option<KeyCode>,
+    pub x: f32,
+    pub y: f32,
+}
+
+impl ProfilerOverlay {
+    /// The font is loaded from the bytes given when the app starts
+    pub fn new(font_data: &'static [u8]) -> Self {
+        Self {
+            font_data,
+            font: None,
+            visible: true,
+            toggle_key: Some(KeyCode::F3),
+            x: 10.0,
+            y: 10.0,
+        }
+    }
+}
+
+impl Plugin for ProfilerOverlay {
+    fn pre_start(&mut self, app: &mut App) {
+        match Font::from_bytes(app, self.font_data) {
+            Ok(font) => self.font = Some(font),
+            Err(e) => log::error!("Profiler overlay font: {}", e),
+        }
+    }
+
+    fn post_draw(&mut self, app: &mut App) {
+        let font = match &self.font {
+            Some(font) if self.visible => font,
+            _ => return,
+        };
+
+        let before = app.gfx().stats();
+
+        // the profiler is moved out while the app is borrowed to draw
+        let profiler = std::mem::replace(&mut app.profiler, Profiler::new());
+        let draw = app.draw();
+        draw.begin_without_clear();
+        profiler.draw_overlay(draw, font, self.x, self.y);
+        draw.end();
+        app.profiler = profiler;
+
+        let after = app.gfx().stats();
+        app.profiler.exclude(sub_gfx(&after, &before));
+    }
+
+    fn event(&mut self, _app: &mut App, event: Event) -> Option<Event> {
+        match event {
+            Event::KeyDown {
+                key,
+                is_repeat: false,
+                ..
+            } if Some(key) == self.toggle_key => {
+                self.visible = !self.visible;
+                None
+            }
+            Event::KeyDown { key, .. } | Event::KeyUp { key, .. }
+                if Some(key) == self.toggle_key =>
+            {
+                None
+            }
+            _ => Some(event),
+        }
+    }
+}
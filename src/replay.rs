@@ -0,0 +1,152 @@
+use nae_core::{Event, EventIterator};
+use serde::{Deserialize, Serialize};
+
+/// Events and delta time of one recorded frame
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    pub frame: u64,
+    pub delta: f32,
+    pub events: Vec<Event>,
+}
+
+/// Input session recorded with `App::start_recording` that can be replayed with `App::replay`.
+/// Use a seeded `Random` and store the seed along with the record to reproduce the session
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InputRecord {
+    pub frames: Vec<RecordedFrame>,
+}
+
+impl InputRecord {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
+        serde_json::from_slice(data).map_err(|e| e.to_string())
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        serde_json::to_vec(self).map_err(|e| e.to_string())
+    }
+
+    /// Load a record from a file
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load(path: &str) -> Result<Self, String> {
+        let data = std::fs::read(path).map_err(|e| e.to_string())?;
+        Self::from_bytes(&data)
+    }
+
+    /// Save the record to a file
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        std::fs::write(path, self.to_bytes()?).map_err(|e| e.to_string())
+    }
+
+    /// Number of frames recorded
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
+
+pub(crate) enum Replay {
+    None,
+    Recording(InputRecord),
+    Replaying { record: InputRecord, index: usize },
+}
+
+impl Replay {
+    /// Record or replace the events queued by the backend for this frame.
+    /// Returns the delta time to use in the frame
+    pub fn process(&mut self, events: &mut EventIterator, delta: f32) -> f32 {
+        match self {
+            Replay::None => delta,
+            Replay::Recording(record) => {
+                record.frames.push(RecordedFrame {
+                    frame: record.frames.len() as u64,
+                    delta,
+                    events: events.iter().cloned().collect(),
+                });
+                delta
+            }
+            Replay::Replaying { record, index } => {
+                let frame = match record.frames.get(*index) {
+                    Some(frame) => frame.clone(),
+                    _ => {
+                        *self = Replay::None;
+                        return delta;
+                    }
+                };
+                *index += 1;
+
+                // live input is ignored while replaying but the app can still be closed
+                let quit = events.take_events().any(|evt| evt == Event::Quit);
+                frame.events.into_iter().for_each(|evt| events.push(evt));
+                if quit {
+                    events.push(Event::Quit);
+                }
+
+                frame.delta
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use nae_core::{KeyCode, Modifiers};
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::*;
+
+    fn key_down(key: KeyCode) -> Event {
+        Event::KeyDown {
+            key,
+            modifiers: Modifiers::SHIFT,
+            is_repeat: false,
+        }
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_record_and_replay() {
+        let mut replay = Replay::Recording(InputRecord::new());
+        let mut events = EventIterator::new();
+        events.push(key_down(KeyCode::A));
+        assert_eq!(replay.process(&mut events, 0.016), 0.016);
+        events.take_events();
+        events.push(Event::MouseMove { x: 10, y: 20 });
+        assert_eq!(replay.process(&mut events, 0.02), 0.02);
+        events.take_events();
+
+        let record = match replay {
+            Replay::Recording(record) => record,
+            _ => panic!(),
+        };
+        assert_eq!(record.len(), 2);
+
+        let bytes = record.to_bytes().unwrap();
+        let record = InputRecord::from_bytes(&bytes).unwrap();
+
+        let mut replay = Replay::Replaying { record, index: 0 };
+        events.push(key_down(KeyCode::B));
+        assert_eq!(replay.process(&mut events, 0.5), 0.016);
+        let evts: Vec<Event> = events.take_events().collect();
+        assert_eq!(evts, vec![key_down(KeyCode::A)]);
+
+        assert_eq!(replay.process(&mut events, 0.5), 0.02);
+        let evts: Vec<Event> = events.take_events().collect();
+        assert_eq!(evts, vec![Event::MouseMove { x: 10, y: 20 }]);
+
+        //Live input is used again after the last frame
+        assert_eq!(replay.process(&mut events, 0.5), 0.5);
+        match replay {
+            Replay::None => {}
+            _ => panic!(),
+        }
+    }
+}
@@ -2,26 +2,31 @@ use super::ResourceParser;
 use crate::app::App;
 use crate::resource_parser;
 use backend::{Draw, Graphics};
-use nae_core::{BaseApp, BaseSystem, Resource};
+use nae_core::{BaseApp, BaseSystem, Resource, ResourceState};
 use std::cell::{Ref, RefCell, RefMut};
 use std::rc::Rc;
 
+struct InnerBlob {
+    data: Vec<u8>,
+    state: ResourceState,
+}
+
 /// Represent raw data
 #[derive(Clone)]
 pub struct Blob {
-    inner: Rc<RefCell<Vec<u8>>>,
+    inner: Rc<RefCell<InnerBlob>>,
 }
 
 impl Blob {
     //https://stackoverflow.com/questions/29401626/how-do-i-return-a-reference-to-something-inside-a-refcell-without-breaking-encap
     /// Return a reference to the inner data
     pub fn data(&self) -> Ref<Vec<u8>> {
-        Ref::map(self.inner.borrow(), |data| data)
+        Ref::map(self.inner.borrow(), |inner| &inner.data)
     }
 
     /// Return a mutable referece to the inner data
     pub fn data_mut(&mut self) -> RefMut<Vec<u8>> {
-        RefMut::map(self.inner.borrow_mut(), |data| data)
+        RefMut::map(self.inner.borrow_mut(), |inner| &mut inner.data)
     }
 
     /// Create a new blob from bytes
@@ -31,7 +36,10 @@ impl Blob {
         S: BaseSystem<Graphics = Graphics>,
     {
         let blob = Blob {
-            inner: Rc::new(RefCell::new(data.to_vec())),
+            inner: Rc::new(RefCell::new(InnerBlob {
+                data: data.to_vec(),
+                state: ResourceState::Loaded,
+            })),
         };
 
         Ok(blob)
@@ -39,19 +47,33 @@ impl Blob {
 
     /// Returns if the resource is already loaded
     pub fn is_loaded(&self) -> bool {
-        self.inner.borrow().len() != 0
+        self.inner.borrow().state == ResourceState::Loaded
+    }
+
+    /// Returns the loading state of the resource
+    pub fn state(&self) -> ResourceState {
+        self.inner.borrow().state.clone()
     }
 }
 
 impl Resource<App> for Blob {
     fn prepare(app: &mut App, file: &str) -> Result<Self, String> {
-        Self::from_bytes(app, &[])
+        let blob = Self::from_bytes(app, &[])?;
+        blob.inner.borrow_mut().state = ResourceState::Pending;
+        Ok(blob)
     }
 
     fn set_data(&mut self, app: &mut App, data: Vec<u8>) -> Result<(), String> {
-        *self.inner.borrow_mut() = data;
+        *self.inner.borrow_mut() = InnerBlob {
+            data,
+            state: ResourceState::Loaded,
+        };
         Ok(())
     }
+
+    fn set_error(&mut self, err: String) {
+        self.inner.borrow_mut().state = ResourceState::Failed(err);
+    }
 }
 
 resource_parser!(Blob, App);
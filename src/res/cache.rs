@@ -0,0 +1,168 @@
+use hashbrown::HashMap;
+use std::any::{Any, TypeId};
+
+/// Type and normalized path of a resource
+pub(crate) type ResourceKey = (TypeId, String);
+
+struct CacheEntry {
+    handle: Box<dyn Any>,
+    /// Group of each reference, a reference is added each time the resource is requested
+    groups: Vec<String>,
+    /// `None` while the resource is loading
+    loaded: Option<bool>,
+}
+
+/// Shared handles of the resources requested, the same handle is returned for a path and type
+/// until all its references are released
+pub(crate) struct ResourceCache {
+    entries: HashMap<ResourceKey, CacheEntry>,
+}
+
+impl ResourceCache {
+    pub fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Returns a clone of the handle adding a reference for the group
+    pub fn get<T: Clone + 'static>(&mut self, key: &ResourceKey, group: &str) -> Option<T> {
+        let entry = self.entries.get_mut(key)?;
+        let handle = entry.handle.downcast_ref::<T>()?.clone();
+        entry.groups.push(group.to_string());
+        Some(handle)
+    }
+
+    pub fn insert<T: Clone + 'static>(&mut self, key: ResourceKey, group: &str, handle: &T) {
+        self.entries.insert(
+            key,
+            CacheEntry {
+                handle: Box::new(handle.clone()),
+                groups: vec![group.to_string()],
+                loaded: None,
+            },
+        );
+    }
+
+    /// Returns `Some(true)` if the resource was loaded and `None` if it's still loading
+    pub fn loaded(&self, key: &ResourceKey) -> Option<bool> {
+        self.entries.get(key).and_then(|e| e.loaded)
+    }
+
+    /// The failed resources are removed to load them again the next time they are requested
+    pub fn set_loaded(&mut self, key: &ResourceKey, success: bool) {
+        if !success {
+            self.entries.remove(key);
+        } else if let Some(entry) = self.entries.get_mut(key) {
+            entry.loaded = Some(true);
+        }
+    }
+
+    pub fn references(&self, key: &ResourceKey) -> usize {
+        self.entries.get(key).map_or(0, |e| e.groups.len())
+    }
+
+    /// Group of each reference, from the oldest to the newest
+    pub fn groups(&self, key: &ResourceKey) -> &[String] {
+        self.entries.get(key).map_or(&[], |e| &e.groups)
+    }
+
+    /// Remove the last reference, returns true if the resource was removed from the cache
+    pub fn release(&mut self, key: &ResourceKey) -> bool {
+        let index = match self.entries.get(key) {
+            Some(entry) => entry.groups.len().saturating_sub(1),
+            None => return false,
+        };
+
+        self.remove_reference(key, index)
+    }
+
+    /// Remove the last reference of the group, returns true if the resource was removed
+    /// from the cache
+    pub fn release_in(&mut self, key: &ResourceKey, group: &str) -> bool {
+        let index = self
+            .entries
+            .get(key)
+            .and_then(|entry| entry.groups.iter().rposition(|g| g == group));
+
+        match index {
+            Some(index) => self.remove_reference(key, index),
+            None => false,
+        }
+    }
+
+    fn remove_reference(&mut self, key: &ResourceKey, index: usize) -> bool {
+        let empty = match self.entries.get_mut(key) {
+            Some(entry) if index < entry.groups.len() => {
+                entry.groups.remove(index);
+                entry.groups.is_empty()
+            }
+            _ => return false,
+        };
+
+        if empty {
+            self.entries.remove(key);
+        }
+
+        empty
+    }
+
+    /// Remove the references of the group, returns the resources removed from the cache
+    pub fn release_group(&mut self, group: &str) -> Vec<ResourceKey> {
+        let mut removed = vec![];
+        for (key, entry) in self.entries.iter_mut() {
+            entry.groups.retain(|g| g != group);
+            if entry.groups.is_empty() {
+                removed.push(key.clone());
+            }
+        }
+
+        removed.iter().for_each(|key| {
+            self.entries.remove(key);
+        });
+
+        removed
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::*;
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_references() {
+        let key = (TypeId::of::<String>(), "a.txt".to_string());
+        let mut cache = ResourceCache::new();
+        cache.insert(key.clone(), "level1", &"a".to_string());
+        assert_eq!(cache.get::<String>(&key, "level2"), Some("a".to_string()));
+        assert_eq!(cache.get::<u32>(&key, "level2"), None);
+        assert_eq!(cache.references(&key), 2);
+        assert_eq!(cache.loaded(&key), None);
+
+        cache.set_loaded(&key, true);
+        assert_eq!(cache.loaded(&key), Some(true));
+
+        assert_eq!(cache.groups(&key), &["level1", "level2"]);
+        assert!(!cache.release_in(&key, "level3"));
+        assert!(cache.release_group("level1").is_empty());
+        assert_eq!(cache.references(&key), 1);
+        assert!(cache.release(&key));
+        assert_eq!(cache.references(&key), 0);
+        assert_eq!(cache.get::<String>(&key, "level1"), None);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_failed_resources_are_removed() {
+        let key = (TypeId::of::<String>(), "missing.txt".to_string());
+        let mut cache = ResourceCache::new();
+        cache.insert(key.clone(), "level1", &"a".to_string());
+        cache.set_loaded(&key, false);
+        assert_eq!(cache.references(&key), 0);
+        assert_eq!(cache.get::<String>(&key, "level1"), None);
+    }
+}
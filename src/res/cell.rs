@@ -0,0 +1,89 @@
+use nae_core::ResourceState;
+use std::cell::{Ref, RefCell, RefMut};
+use std::rc::Rc;
+
+struct InnerCell<T> {
+    value: Option<T>,
+    state: ResourceState,
+}
+
+/// Shared value of a resource and its loading state, the clones point to the same value.
+/// It's the field used by the types with `#[derive(Resource)]`
+pub struct ResourceCell<T> {
+    inner: Rc<RefCell<InnerCell<T>>>,
+}
+
+impl<T> ResourceCell<T> {
+    /// Create a cell waiting for the value
+    pub fn new() -> Self {
+        Self {
+            inner: Rc::new(RefCell::new(InnerCell {
+                value: None,
+                state: ResourceState::Pending,
+            })),
+        }
+    }
+
+    /// Create a cell already loaded
+    pub fn with_value(value: T) -> Self {
+        let cell = Self::new();
+        cell.set(value);
+        cell
+    }
+
+    /// Set the value, replacing the previous one, and mark it as loaded
+    pub fn set(&self, value: T) {
+        let mut inner = self.inner.borrow_mut();
+        inner.value = Some(value);
+        inner.state = ResourceState::Loaded;
+    }
+
+    /// Mark it as failed, the previous value is kept if there is any
+    pub fn set_error(&self, err: String) {
+        self.inner.borrow_mut().state = ResourceState::Failed(err);
+    }
+
+    /// Return a reference to the value, `None` if it's not loaded yet
+    pub fn data(&self) -> Option<Ref<T>> {
+        let inner = self.inner.borrow();
+        if inner.value.is_none() {
+            return None;
+        }
+
+        Some(Ref::map(inner, |inner| inner.value.as_ref().unwrap()))
+    }
+
+    /// Return a mutable reference to the value, `None` if it's not loaded yet
+    pub fn data_mut(&self) -> Option<RefMut<T>> {
+        let inner = self.inner.borrow_mut();
+        if inner.value.is_none() {
+            return None;
+        }
+
+        Some(RefMut::map(inner, |inner| inner.value.as_mut().unwrap()))
+    }
+
+    /// Returns if the resource is already loaded
+    pub fn is_loaded(&self) -> bool {
+        self.inner.borrow().state == ResourceState::Loaded
+    }
+
+    /// Returns the loading state of the resource
+    pub fn state(&self) -> ResourceState {
+        self.inner.borrow().state.clone()
+    }
+}
+
+impl<T> Clone for ResourceCell<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> Default for ResourceCell<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
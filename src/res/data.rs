@@ -0,0 +1,157 @@
+use super::{ResourceCell, ResourceParser};
+use crate::app::App;
+use nae_core::{Resource, ResourceState};
+use serde::de::DeserializeOwned;
+use std::cell::{Ref, RefMut};
+use std::path::Path;
+
+/// Format used to deserialize the data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataFormat {
+    Json,
+    #[cfg(feature = "ron")]
+    Ron,
+    #[cfg(feature = "toml")]
+    Toml,
+}
+
+impl DataFormat {
+    /// Returns the format to use for the file's extension, JSON is used for unknown extensions
+    pub fn from_file(file: &str) -> Result<Self, String> {
+        let ext = Path::new(file)
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase());
+
+        match ext.as_deref() {
+            #[cfg(feature = "ron")]
+            Some("ron") => Ok(DataFormat::Ron),
+            #[cfg(not(feature = "ron"))]
+            Some("ron") => Err("The feature 'ron' is needed to load RON files".to_string()),
+            #[cfg(feature = "toml")]
+            Some("toml") => Ok(DataFormat::Toml),
+            #[cfg(not(feature = "toml"))]
+            Some("toml") => Err("The feature 'toml' is needed to load TOML files".to_string()),
+            _ => Ok(DataFormat::Json),
+        }
+    }
+
+    fn parse<T: DeserializeOwned>(&self, data: &[u8]) -> Result<T, String> {
+        match self {
+            DataFormat::Json => serde_json::from_slice(data).map_err(|e| e.to_string()),
+            #[cfg(feature = "ron")]
+            DataFormat::Ron => ron::de::from_bytes(data).map_err(|e| e.to_string()),
+            #[cfg(feature = "toml")]
+            DataFormat::Toml => toml::from_slice(data).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+/// Typed data deserialized from JSON, RON or TOML files
+pub struct Data<T> {
+    cell: ResourceCell<T>,
+    format: DataFormat,
+}
+
+impl<T> Clone for Data<T> {
+    fn clone(&self) -> Self {
+        Self {
+            cell: self.cell.clone(),
+            format: self.format,
+        }
+    }
+}
+
+impl<T: DeserializeOwned> Data<T> {
+    /// Return a reference to the value, `None` if it's not loaded yet
+    pub fn data(&self) -> Option<Ref<T>> {
+        self.cell.data()
+    }
+
+    /// Return a mutable reference to the value, `None` if it's not loaded yet
+    pub fn data_mut(&mut self) -> Option<RefMut<T>> {
+        self.cell.data_mut()
+    }
+
+    /// Create a new resource deserializing the bytes
+    pub fn from_bytes(data: &[u8], format: DataFormat) -> Result<Self, String> {
+        Ok(Self {
+            cell: ResourceCell::with_value(format.parse(data)?),
+            format,
+        })
+    }
+
+    /// Returns if the resource is already loaded
+    pub fn is_loaded(&self) -> bool {
+        self.cell.is_loaded()
+    }
+
+    /// Returns the loading state of the resource
+    pub fn state(&self) -> ResourceState {
+        self.cell.state()
+    }
+}
+
+impl<T: DeserializeOwned> Resource<App> for Data<T> {
+    fn prepare(_app: &mut App, file: &str) -> Result<Self, String> {
+        Ok(Self {
+            cell: ResourceCell::new(),
+            format: DataFormat::from_file(file)?,
+        })
+    }
+
+    fn set_data(&mut self, _app: &mut App, data: Vec<u8>) -> Result<(), String> {
+        // the previous value is kept if the new data can't be parsed
+        self.cell.set(self.format.parse(&data)?);
+        Ok(())
+    }
+
+    fn set_error(&mut self, err: String) {
+        self.cell.set_error(err);
+    }
+}
+
+impl<T: DeserializeOwned> ResourceParser for Data<T> {
+    type App = App;
+
+    fn parse_resource(&mut self, app: &mut App, data: Vec<u8>) -> Result<(), String> {
+        self.set_data(app, data)
+    }
+
+    fn set_error(&mut self, err: String) {
+        <Self as Resource<App>>::set_error(self, err)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde::Deserialize;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::*;
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Level {
+        name: String,
+        enemies: u32,
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_json_data() {
+        assert_eq!(DataFormat::from_file("levels/1.JSON"), Ok(DataFormat::Json));
+
+        let json = br#"{ "name": "forest", "enemies": 3 }"#;
+        let level = Data::<Level>::from_bytes(json, DataFormat::Json).unwrap();
+        assert!(level.is_loaded());
+        assert_eq!(
+            *level.data().unwrap(),
+            Level {
+                name: "forest".to_string(),
+                enemies: 3
+            }
+        );
+
+        assert!(Data::<Level>::from_bytes(br#"{ "name": 3 }"#, DataFormat::Json).is_err());
+    }
+}
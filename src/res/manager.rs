@@ -1,59 +1,255 @@
-use super::ResourceParser;
+use super::{ResourceKey, ResourceParser};
 use crate::app::App;
-use backend::load_file;
+use crate::file::FileFuture;
 use backend::{Graphics, System};
 use futures::{Async, Future};
+use hashbrown::HashMap;
 use nae_core::BaseSystem;
 use nae_core::*;
 
-type ResourceLoader = (
-    Box<dyn ResourceParser<App = App>>,
-    Box<dyn Future<Item = Vec<u8>, Error = String>>,
-);
+/// Group used by `App::load_resource`
+pub const DEFAULT_GROUP: &str = "default";
+
+struct ResourceLoader {
+    file: String,
+    key: ResourceKey,
+    /// A group is added each time the resource is requested while it's loading
+    groups: Vec<String>,
+    parser: Box<dyn ResourceParser<App = App>>,
+    future: FileFuture,
+}
+
+/// Resource waiting for the data with the result of reading its file
+pub(crate) struct LoadedResource {
+    pub file: String,
+    pub key: ResourceKey,
+    pub groups: Vec<String>,
+    pub result: Result<Vec<u8>, String>,
+    pub parser: Box<dyn ResourceParser<App = App>>,
+}
+
+/// Number of resources added to a group and how many of them finished loading
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceGroup {
+    pub total: usize,
+    pub loaded: usize,
+    pub failed: usize,
+}
+
+impl ResourceGroup {
+    /// Resources still loading
+    pub fn pending(&self) -> usize {
+        self.total - self.loaded - self.failed
+    }
+
+    /// Value from 0.0 to 1.0, the failed resources count as finished
+    pub fn progress(&self) -> f32 {
+        if self.total == 0 {
+            return 1.0;
+        }
+
+        (self.loaded + self.failed) as f32 / self.total as f32
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.pending() == 0
+    }
+}
 
 pub(crate) struct ResourceLoaderManager {
     to_load: Vec<ResourceLoader>,
+    /// Groups added to the resources already loaded that are waiting to be parsed
+    to_parse: HashMap<ResourceKey, Vec<String>>,
+    groups: HashMap<String, ResourceGroup>,
+    completed: Vec<String>,
+    parsing_group: Option<String>,
 }
 
 impl ResourceLoaderManager {
     pub fn new() -> Self {
-        Self { to_load: vec![] }
-    }
-
-    pub fn len(&self) -> usize {
-        self.to_load.len()
+        Self {
+            to_load: vec![],
+            to_parse: HashMap::new(),
+            groups: HashMap::new(),
+            completed: vec![],
+            parsing_group: None,
+        }
     }
 
-    pub fn add<T>(&mut self, file: &str, resource: Box<T>) -> Result<(), String>
+    pub fn add<T>(
+        &mut self,
+        file: &str,
+        key: ResourceKey,
+        group: &str,
+        resource: Box<T>,
+        future: FileFuture,
+    ) -> Result<(), String>
     where
         T: Resource<App> + ResourceParser<App = App> + 'static,
     {
-        let fut = load_file(file);
-        self.to_load.push((resource, Box::new(fut)));
+        self.groups.entry(group.to_string()).or_default().total += 1;
+        self.to_load.push(ResourceLoader {
+            file: file.to_string(),
+            key,
+            groups: vec![group.to_string()],
+            parser: resource,
+            future,
+        });
         Ok(())
     }
 
-    pub fn try_load(
-        &mut self,
-    ) -> Result<Option<Vec<(Vec<u8>, Box<dyn ResourceParser<App = App>>)>>, String> {
+    /// Count a resource already requested as part of the group too, `loaded` is `None`
+    /// if the resource is still loading
+    pub fn add_to_group(&mut self, key: &ResourceKey, group: &str, loaded: Option<bool>) {
+        self.groups.entry(group.to_string()).or_default().total += 1;
+
+        let loader = self.to_load.iter_mut().find(|loader| loader.key == *key);
+        match (loaded, loader) {
+            (Some(success), _) => self.finish(&[group.to_string()], success),
+            (None, Some(loader)) => loader.groups.push(group.to_string()),
+            // the data is loaded but it's not parsed yet, it's counted with `finish_resource`
+            (None, None) => self
+                .to_parse
+                .entry(key.clone())
+                .or_default()
+                .push(group.to_string()),
+        }
+    }
+
+    /// Group of the resource being parsed, the resources loaded while parsing another
+    /// one (like the texture of an atlas) go to its group
+    pub fn parsing_group(&self) -> Option<&str> {
+        self.parsing_group.as_deref()
+    }
+
+    pub fn set_parsing_group(&mut self, group: Option<String>) {
+        self.parsing_group = group;
+    }
+
+    /// Count a resource as finished, the groups are marked as completed when it was the last one
+    fn finish(&mut self, groups: &[String], success: bool) {
+        for group in groups {
+            if let Some(info) = self.groups.get_mut(group) {
+                if success {
+                    info.loaded += 1;
+                } else {
+                    info.failed += 1;
+                }
+
+                if info.is_complete() {
+                    self.completed.push(group.to_string());
+                }
+            }
+        }
+    }
+
+    /// Count a resource parsed as finished on its groups and on the ones added while
+    /// it was waiting to be parsed
+    pub fn finish_resource(&mut self, key: &ResourceKey, groups: &[String], success: bool) {
+        self.finish(groups, success);
+        if let Some(groups) = self.to_parse.remove(key) {
+            self.finish(&groups, success);
+        }
+    }
+
+    /// Returns the groups completed since the last call
+    pub fn take_completed(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.completed)
+    }
+
+    pub fn group(&self, name: &str) -> Option<&ResourceGroup> {
+        self.groups.get(name)
+    }
+
+    pub fn groups(&self) -> impl Iterator<Item = (&str, &ResourceGroup)> {
+        self.groups
+            .iter()
+            .map(|(name, group)| (name.as_str(), group))
+    }
+
+    /// Forget the group and cancel its resources still loading if no other group needs them
+    pub fn remove_group(&mut self, name: &str) {
+        self.groups.remove(name);
+        self.completed.retain(|g| g != name);
+        self.to_load.iter_mut().for_each(|loader| {
+            loader.groups.retain(|g| g != name);
+        });
+        self.to_load.retain(|loader| !loader.groups.is_empty());
+        self.to_parse.values_mut().for_each(|groups| {
+            groups.retain(|g| g != name);
+        });
+    }
+
+    /// Stop loading a resource, it's no longer counted on its groups
+    pub fn cancel(&mut self, key: &ResourceKey) {
+        let mut groups = self.to_parse.remove(key).unwrap_or_default();
+        if let Some(index) = self.to_load.iter().position(|loader| loader.key == *key) {
+            groups.extend(self.to_load.remove(index).groups);
+        }
+
+        for group in groups {
+            self.uncount(&group);
+        }
+    }
+
+    /// Stop counting a resource still loading on one of the groups that requested it
+    pub fn remove_from_group(&mut self, key: &ResourceKey, group: &str) {
+        let groups = match self.to_load.iter_mut().find(|loader| loader.key == *key) {
+            Some(loader) => Some(&mut loader.groups),
+            None => self.to_parse.get_mut(key),
+        };
+
+        let index = groups
+            .as_ref()
+            .and_then(|groups| groups.iter().rposition(|g| g == group));
+
+        if let (Some(groups), Some(index)) = (groups, index) {
+            groups.remove(index);
+            self.uncount(group);
+        }
+    }
+
+    fn uncount(&mut self, group: &str) {
+        if let Some(info) = self.groups.get_mut(group) {
+            info.total -= 1;
+            if info.is_complete() {
+                self.completed.push(group.to_string());
+            }
+        }
+    }
+
+    /// Returns the resources that finished loading, successfully or not
+    pub fn try_load(&mut self) -> Option<Vec<LoadedResource>> {
         if self.to_load.len() == 0 {
-            return Ok(None);
+            return None;
         }
 
         let mut loaded = vec![];
         let mut not_loaded = vec![];
 
         while let Some(mut asset_loader) = self.to_load.pop() {
-            if let AssetState::Done(data) = try_load_asset(&mut asset_loader)? {
-                loaded.push((data, asset_loader.0));
-            } else {
-                not_loaded.push(asset_loader);
+            match try_load_asset(&mut asset_loader) {
+                AssetState::Done(data) => loaded.push(asset_loader.into_loaded(Ok(data))),
+                AssetState::Failed(err) => loaded.push(asset_loader.into_loaded(Err(err))),
+                _ => not_loaded.push(asset_loader),
             }
         }
 
         self.to_load = not_loaded;
 
-        Ok(Some(loaded))
+        Some(loaded)
+    }
+}
+
+impl ResourceLoader {
+    fn into_loaded(self, result: Result<Vec<u8>, String>) -> LoadedResource {
+        LoadedResource {
+            file: self.file,
+            key: self.key,
+            groups: self.groups,
+            result,
+            parser: self.parser,
+        }
     }
 }
 
@@ -62,15 +258,74 @@ pub(crate) enum AssetState {
     OnProgress,
     AlreadyLoaded,
     Done(Vec<u8>),
+    Failed(String),
 }
 
-fn try_load_asset(loader: &mut ResourceLoader) -> Result<AssetState, String> {
-    let (_, ref mut future) = loader;
-    return future.poll().map(|s| {
-        if let Async::Ready(buff) = s {
-            AssetState::Done(buff)
-        } else {
-            AssetState::OnProgress
-        }
-    });
+fn try_load_asset(loader: &mut ResourceLoader) -> AssetState {
+    match loader.future.poll() {
+        Ok(Async::Ready(buff)) => AssetState::Done(buff),
+        Ok(Async::NotReady) => AssetState::OnProgress,
+        Err(err) => AssetState::Failed(err),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::any::TypeId;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::*;
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_group_added_before_parsing() {
+        let key = (TypeId::of::<String>(), "a.png".to_string());
+        let mut manager = ResourceLoaderManager::new();
+
+        // the data is loaded but the resource is not parsed yet
+        manager.add_to_group(&key, "level", None);
+        assert_eq!(manager.group("level").map(|g| g.pending()), Some(1));
+        assert!(manager.take_completed().is_empty());
+
+        manager.finish_resource(&key, &[], false);
+        assert_eq!(
+            manager.group("level"),
+            Some(&ResourceGroup {
+                total: 1,
+                loaded: 0,
+                failed: 1
+            })
+        );
+        assert_eq!(manager.take_completed(), vec!["level".to_string()]);
+
+        // it's only counted once
+        manager.finish_resource(&key, &[], true);
+        assert_eq!(manager.group("level").map(|g| g.loaded), Some(0));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_remove_from_group() {
+        let key = (TypeId::of::<String>(), "a.png".to_string());
+        let mut manager = ResourceLoaderManager::new();
+        manager.add_to_group(&key, "level", None);
+        manager.add_to_group(&key, "menu", None);
+
+        manager.remove_from_group(&key, "level");
+        assert_eq!(manager.group("level").map(|g| g.total), Some(0));
+        assert_eq!(manager.take_completed(), vec!["level".to_string()]);
+
+        // the group doesn't reference it anymore
+        manager.remove_from_group(&key, "level");
+        assert_eq!(manager.group("level").map(|g| g.total), Some(0));
+
+        manager.cancel(&key);
+        assert_eq!(manager.group("menu").map(|g| g.progress()), Some(1.0));
+        assert_eq!(manager.take_completed(), vec!["menu".to_string()]);
+
+        manager.finish_resource(&key, &[], true);
+        assert_eq!(manager.group("level").map(|g| g.loaded), Some(0));
+        assert_eq!(manager.group("menu").map(|g| g.loaded), Some(0));
+    }
 }
@@ -1,16 +1,28 @@
 mod blob;
+mod cache;
+mod cell;
+mod data;
 mod manager;
+mod reload;
 
-pub use backend::{BaseApp, Font, Resource, System, Texture};
+pub use backend::{BaseApp, Font, Resource, ResourceState, System, Texture};
 
 use crate::app::App;
 pub use blob::*;
+pub(crate) use cache::*;
+pub use cell::ResourceCell;
+pub use data::*;
 pub(crate) use manager::*;
+pub use manager::{ResourceGroup, DEFAULT_GROUP};
+pub(crate) use reload::HotReloader;
 
 pub trait ResourceParser {
     type App: BaseApp;
 
     fn parse_resource(&mut self, app: &mut Self::App, data: Vec<u8>) -> Result<(), String>;
+
+    /// Set the resource as failed when the file can't be loaded or parsed
+    fn set_error(&mut self, err: String);
 }
 
 #[macro_export]
@@ -22,6 +34,10 @@ macro_rules! resource_parser {
             fn parse_resource(&mut self, app: &mut $app, data: Vec<u8>) -> Result<(), String> {
                 self.set_data(app, data)
             }
+
+            fn set_error(&mut self, err: String) {
+                <Self as Resource<$app>>::set_error(self, err)
+            }
         }
     };
 }
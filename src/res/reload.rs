@@ -0,0 +1,100 @@
+use super::{ResourceKey, ResourceParser};
+use crate::app::App;
+use nae_core::{log, BaseApp, BaseSystem, Event};
+use std::fs;
+use std::time::SystemTime;
+
+struct WatchedResource {
+    file: String,
+    key: ResourceKey,
+    path: String,
+    modified: Option<SystemTime>,
+    parser: Box<dyn ResourceParser<App = App>>,
+}
+
+/// Check the files on the disk of the resources loaded and parse them again when they change
+pub(crate) struct HotReloader {
+    interval: f32,
+    elapsed: f32,
+    watched: Vec<WatchedResource>,
+}
+
+impl HotReloader {
+    pub fn new(interval: f32) -> Self {
+        Self {
+            interval,
+            elapsed: 0.0,
+            watched: vec![],
+        }
+    }
+
+    /// Watch the file on the disk used by the resource
+    pub fn watch(
+        &mut self,
+        file: String,
+        key: ResourceKey,
+        path: String,
+        parser: Box<dyn ResourceParser<App = App>>,
+    ) {
+        self.watched.push(WatchedResource {
+            modified: modified(&path),
+            file,
+            key,
+            path,
+            parser,
+        });
+    }
+
+    /// Stop watching a resource removed from the cache
+    pub fn unwatch(&mut self, key: &ResourceKey) {
+        self.watched.retain(|res| res.key != *key);
+    }
+
+    /// Keep watching only the resources that match the predicate
+    pub fn retain<F: FnMut(&ResourceKey) -> bool>(&mut self, mut f: F) {
+        self.watched.retain(|res| f(&res.key));
+    }
+
+    /// Parse again the resources changed since the last check
+    pub fn update(&mut self, app: &mut App) {
+        self.elapsed += app.delta;
+        if self.elapsed < self.interval {
+            return;
+        }
+        self.elapsed = 0.0;
+
+        for res in self.watched.iter_mut() {
+            let modified = modified(&res.path);
+            if modified.is_none() || modified == res.modified {
+                continue;
+            }
+
+            // if the file is still being written it will be read again on the next check
+            let data = match fs::read(&res.path) {
+                Ok(data) => data,
+                Err(_) => continue,
+            };
+            res.modified = modified;
+
+            // the resource keeps the old data if it can't be parsed
+            let evt = match app.reparse_resource(&res.key, res.parser.as_mut(), data) {
+                Ok(_) => Event::ResourceReloaded {
+                    file: res.file.clone(),
+                },
+                Err(error) => {
+                    log::error!("Error reloading '{}': {}", res.file, error);
+                    Event::ResourceFailed {
+                        file: res.file.clone(),
+                        error,
+                    }
+                }
+            };
+
+            app.system().events().push(evt);
+        }
+    }
+}
+
+fn modified(path: &str) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
@@ -0,0 +1,255 @@
+use crate::app::{App, AppBuilder};
+use nae_core::{Color, Event};
+
+/// Represents a screen of the app like a menu, the gameplay or a pause screen
+pub trait Scene {
+    /// Called when the scene is added to the stack
+    fn start(&mut self, _app: &mut App) {}
+
+    /// Called each frame while the scene is on top of the stack
+    fn update(&mut self, _app: &mut App) -> SceneAction {
+        SceneAction::None
+    }
+
+    /// Called each frame while the scene is on top of the stack, or below it if
+    /// `draw_when_paused` returns true. Use `Draw::begin_without_clear` to draw on top of
+    /// the paused scenes
+    fn draw(&mut self, _app: &mut App) {}
+
+    /// Called with each event while the scene is on top of the stack
+    fn event(&mut self, _app: &mut App, _event: Event) -> SceneAction {
+        SceneAction::None
+    }
+
+    /// Called when the scene is removed from the stack
+    fn exit(&mut self, _app: &mut App) {}
+
+    /// Keep drawing this scene when other scene is pushed on top of it
+    fn draw_when_paused(&self) -> bool {
+        false
+    }
+}
+
+/// Change to apply to the scene stack
+pub enum SceneAction {
+    None,
+    Push(Box<dyn Scene>),
+    Pop,
+    Replace(Box<dyn Scene>),
+    WithEffect(Box<SceneAction>, Box<dyn TransitionEffect>),
+}
+
+impl SceneAction {
+    pub fn push<S: Scene + 'static>(scene: S) -> Self {
+        SceneAction::Push(Box::new(scene))
+    }
+
+    pub fn replace<S: Scene + 'static>(scene: S) -> Self {
+        SceneAction::Replace(Box::new(scene))
+    }
+
+    /// Apply this action in the middle of the transition effect
+    pub fn with_effect<E: TransitionEffect + 'static>(self, effect: E) -> Self {
+        SceneAction::WithEffect(Box::new(self), Box::new(effect))
+    }
+}
+
+/// Effect drawn on top of the scenes while the stack changes
+pub trait TransitionEffect {
+    /// Duration in seconds, the scenes are switched at the middle
+    fn duration(&self) -> f32;
+
+    /// Draw the effect, progress goes from 0.0 to 1.0
+    fn draw(&mut self, app: &mut App, progress: f32);
+}
+
+/// Fade to a color and back
+pub struct FadeEffect {
+    pub color: Color,
+    pub duration: f32,
+}
+
+impl FadeEffect {
+    pub fn new(color: Color, duration: f32) -> Self {
+        Self { color, duration }
+    }
+}
+
+impl TransitionEffect for FadeEffect {
+    fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    fn draw(&mut self, app: &mut App, progress: f32) {
+        let alpha = 1.0 - (progress * 2.0 - 1.0).abs();
+        let (width, height) = (app.width(), app.height());
+
+        let draw = app.draw();
+        let color = draw.color;
+        draw.begin_without_clear();
+        draw.color = self.color.with_alpha(alpha);
+        draw.rect(0.0, 0.0, width, height);
+        draw.end();
+        draw.color = color;
+    }
+}
+
+struct Transition {
+    action: Option<SceneAction>,
+    effect: Box<dyn TransitionEffect>,
+    time: f32,
+}
+
+impl Transition {
+    fn progress(&self) -> f32 {
+        let duration = self.effect.duration();
+        if duration <= 0.0 {
+            return 1.0;
+        }
+
+        (self.time / duration).min(1.0)
+    }
+}
+
+/// Stack of scenes, just the scene on top is updated
+pub struct SceneManager {
+    stack: Vec<Box<dyn Scene>>,
+    transition: Option<Transition>,
+}
+
+impl SceneManager {
+    pub fn new<S: Scene + 'static>(app: &mut App, scene: S) -> Self {
+        let mut manager = Self {
+            stack: vec![],
+            transition: None,
+        };
+
+        manager.push(app, scene);
+        manager
+    }
+
+    /// Add a scene on top of the stack pausing the current one
+    pub fn push<S: Scene + 'static>(&mut self, app: &mut App, scene: S) {
+        self.apply(app, SceneAction::push(scene));
+    }
+
+    /// Remove the scene on top of the stack resuming the one below
+    pub fn pop(&mut self, app: &mut App) {
+        self.apply(app, SceneAction::Pop);
+    }
+
+    /// Replace the scene on top of the stack
+    pub fn replace<S: Scene + 'static>(&mut self, app: &mut App, scene: S) {
+        self.apply(app, SceneAction::replace(scene));
+    }
+
+    pub fn len(&self) -> usize {
+        self.stack.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    /// Returns if a transition effect is running
+    pub fn in_transition(&self) -> bool {
+        self.transition.is_some()
+    }
+
+    pub fn apply(&mut self, app: &mut App, action: SceneAction) {
+        match action {
+            SceneAction::None => {}
+            SceneAction::Push(mut scene) => {
+                scene.start(app);
+                self.stack.push(scene);
+            }
+            SceneAction::Pop => {
+                if let Some(mut scene) = self.stack.pop() {
+                    scene.exit(app);
+                }
+            }
+            SceneAction::Replace(scene) => {
+                self.apply(app, SceneAction::Pop);
+                self.apply(app, SceneAction::Push(scene));
+            }
+            SceneAction::WithEffect(action, effect) => {
+                // a running transition can't be interrupted
+                if self.transition.is_some() {
+                    return self.apply(app, *action);
+                }
+
+                self.transition = Some(Transition {
+                    action: Some(*action),
+                    effect,
+                    time: 0.0,
+                });
+            }
+        }
+    }
+
+    /// Update callback for `AppBuilder`
+    pub fn update(app: &mut App, manager: &mut SceneManager) {
+        if let Some(mut transition) = manager.transition.take() {
+            transition.time += app.delta;
+            if transition.progress() >= 0.5 {
+                if let Some(action) = transition.action.take() {
+                    manager.apply(app, action);
+                }
+            }
+
+            if transition.progress() < 1.0 {
+                manager.transition = Some(transition);
+            }
+
+            return;
+        }
+
+        let action = match manager.stack.last_mut() {
+            Some(scene) => scene.update(app),
+            _ => SceneAction::None,
+        };
+
+        manager.apply(app, action);
+    }
+
+    /// Draw callback for `AppBuilder`
+    pub fn draw(app: &mut App, manager: &mut SceneManager) {
+        let len = manager.stack.len();
+        let mut first = len.saturating_sub(1);
+        while first > 0 && manager.stack[first - 1].draw_when_paused() {
+            first -= 1;
+        }
+
+        manager.stack[first..]
+            .iter_mut()
+            .for_each(|scene| scene.draw(app));
+
+        if let Some(transition) = &mut manager.transition {
+            let progress = transition.progress();
+            transition.effect.draw(app, progress);
+        }
+    }
+
+    /// Event callback for `AppBuilder`
+    pub fn event(app: &mut App, manager: &mut SceneManager, event: Event) {
+        if manager.transition.is_some() {
+            return;
+        }
+
+        let action = match manager.stack.last_mut() {
+            Some(scene) => scene.event(app, event),
+            _ => SceneAction::None,
+        };
+
+        manager.apply(app, action);
+    }
+}
+
+impl AppBuilder<SceneManager> {
+    /// Use the scene manager to update, draw and dispatch the events
+    pub fn scenes(&mut self) -> &mut Self {
+        self.update(SceneManager::update)
+            .draw(SceneManager::draw)
+            .event(SceneManager::event)
+    }
+}
@@ -0,0 +1,345 @@
+use hashbrown::HashSet;
+use nae_core::{Event, GamepadButton, KeyCode, MouseButton};
+use std::collections::VecDeque;
+
+/// Button stored on the input buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputButton {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    /// Button of any connected gamepad
+    Gamepad(GamepadButton),
+}
+
+impl From<KeyCode> for InputButton {
+    fn from(key: KeyCode) -> Self {
+        InputButton::Key(key)
+    }
+}
+
+impl From<MouseButton> for InputButton {
+    fn from(btn: MouseButton) -> Self {
+        InputButton::Mouse(btn)
+    }
+}
+
+impl From<GamepadButton> for InputButton {
+    fn from(btn: GamepadButton) -> Self {
+        InputButton::Gamepad(btn)
+    }
+}
+
+/// Press or release of a button with the time in seconds it happened
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BufferedInput {
+    pub button: InputButton,
+    pub pressed: bool,
+    pub time: f32,
+    id: u64,
+}
+
+/// Ordered steps to match with the input buffer, each step is a set of buttons down at the same time
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sequence {
+    steps: Vec<Vec<InputButton>>,
+    /// Max time in seconds between two steps
+    pub step_time: f32,
+}
+
+impl Sequence {
+    pub fn new(step_time: f32) -> Self {
+        Self {
+            steps: vec![],
+            step_time,
+        }
+    }
+
+    /// Add a step with one button
+    pub fn then<B: Into<InputButton>>(mut self, button: B) -> Self {
+        self.steps.push(vec![button.into()]);
+        self
+    }
+
+    /// Add a step with buttons that must be down at the same time (e.g. Down + Right)
+    pub fn then_all<B: Into<InputButton>>(mut self, buttons: Vec<B>) -> Self {
+        self.steps
+            .push(buttons.into_iter().map(|b| b.into()).collect());
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+/// Remember the last button events to match double taps, holds and sequences.
+/// The patterns match only during the frame their last input happened
+pub struct InputBuffer {
+    /// Max number of inputs remembered
+    pub capacity: usize,
+
+    inputs: VecDeque<BufferedInput>,
+    time: f32,
+    next_id: u64,
+    frame_id: u64,
+}
+
+impl InputBuffer {
+    pub fn new() -> Self {
+        Self {
+            capacity: 32,
+            inputs: VecDeque::new(),
+            time: 0.0,
+            next_id: 0,
+            frame_id: 0,
+        }
+    }
+
+    /// Inputs remembered from the oldest to the newest
+    pub fn iter(&self) -> impl Iterator<Item = &BufferedInput> {
+        self.inputs.iter()
+    }
+
+    /// Time in seconds since the buffer was created
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    /// Forget all the inputs
+    pub fn reset(&mut self) {
+        self.inputs.clear();
+    }
+
+    /// Returns true if the button was pressed this frame and the previous press was
+    /// at most `max_time` seconds before
+    pub fn double_tap<B: Into<InputButton>>(&self, button: B, max_time: f32) -> bool {
+        let button = button.into();
+        let mut presses = self
+            .inputs
+            .iter()
+            .rev()
+            .filter(|i| i.pressed && i.button == button);
+
+        match (presses.next(), presses.next()) {
+            (Some(last), Some(prev)) => self.is_current(last) && last.time - prev.time <= max_time,
+            _ => false,
+        }
+    }
+
+    /// Returns true if the button was released this frame after being down at least `min_time` seconds
+    pub fn held_and_released<B: Into<InputButton>>(&self, button: B, min_time: f32) -> bool {
+        let button = button.into();
+        let mut inputs = self.inputs.iter().rev().filter(|i| i.button == button);
+
+        match (inputs.next(), inputs.next()) {
+            (Some(release), Some(press)) => {
+                self.is_current(release)
+                    && !release.pressed
+                    && press.pressed
+                    && release.time - press.time >= min_time
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true if the last step of the sequence was done this frame
+    pub fn sequence(&self, sequence: &Sequence) -> bool {
+        if sequence.is_empty() {
+            return false;
+        }
+
+        // the last input of the sequence must be on this frame
+        let last_index = match self.inputs.iter().rposition(|i| self.is_current(i)) {
+            Some(index) => index,
+            _ => return false,
+        };
+
+        (0..=last_index).any(|start| self.match_from(start, last_index, sequence))
+    }
+
+    fn match_from(&self, start: usize, last_index: usize, sequence: &Sequence) -> bool {
+        // buttons down before the start, the ones pressed before the buffer are unknown
+        let mut down = HashSet::new();
+        self.inputs
+            .iter()
+            .take(start)
+            .for_each(|i| apply(&mut down, i));
+
+        let mut step = 0;
+        let mut last_time: Option<f32> = None;
+        for (index, input) in self.inputs.iter().enumerate().skip(start) {
+            if index > last_index {
+                break;
+            }
+
+            apply(&mut down, input);
+
+            let buttons = &sequence.steps[step];
+            let relevant = if input.pressed {
+                buttons.contains(&input.button)
+            } else {
+                // releasing a button can complete a step like Down + Right -> Right
+                step > 0 && sequence.steps[step - 1].contains(&input.button)
+            };
+
+            if !relevant {
+                continue;
+            }
+
+            if let Some(time) = last_time {
+                if input.time - time > sequence.step_time {
+                    return false;
+                }
+            }
+
+            if buttons.iter().all(|b| down.contains(b)) {
+                step += 1;
+                last_time = Some(input.time);
+                if step == sequence.len() {
+                    return self.is_current(input);
+                }
+            }
+        }
+
+        false
+    }
+
+    fn is_current(&self, input: &BufferedInput) -> bool {
+        input.id >= self.frame_id
+    }
+
+    /// Start a new frame, the inputs of the previous frames are kept.
+    /// The app calls it every frame, it's public to drive the buffer manually on tests
+    pub fn next_frame(&mut self) {
+        self.frame_id = self.next_id;
+    }
+
+    /// Advance the time of the buffer in seconds
+    pub fn tick(&mut self, delta: f32) {
+        self.time += delta;
+    }
+
+    /// Store the button pressed or released by the event, other events are ignored
+    pub fn process(&mut self, evt: &Event) {
+        let (button, pressed) = match evt {
+            Event::KeyDown {
+                key,
+                is_repeat: false,
+                ..
+            } => (InputButton::Key(*key), true),
+            Event::KeyUp { key, .. } => (InputButton::Key(*key), false),
+            Event::MouseDown { button, .. } => (InputButton::Mouse(*button), true),
+            Event::MouseUp { button, .. } => (InputButton::Mouse(*button), false),
+            Event::GamepadButtonDown { button, .. } => (InputButton::Gamepad(*button), true),
+            Event::GamepadButtonUp { button, .. } => (InputButton::Gamepad(*button), false),
+            _ => return,
+        };
+
+        self.inputs.push_back(BufferedInput {
+            button,
+            pressed,
+            time: self.time,
+            id: self.next_id,
+        });
+        self.next_id += 1;
+
+        while self.inputs.len() > self.capacity {
+            self.inputs.pop_front();
+        }
+    }
+}
+
+impl Default for InputBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn apply(down: &mut HashSet<InputButton>, input: &BufferedInput) {
+    if input.pressed {
+        down.insert(input.button);
+    } else {
+        down.remove(&input.button);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use nae_core::Modifiers;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::*;
+
+    fn frame(buffer: &mut InputBuffer, delta: f32, events: Vec<Event>) {
+        buffer.next_frame();
+        buffer.tick(delta);
+        events.iter().for_each(|evt| buffer.process(evt));
+    }
+
+    fn down(key: KeyCode) -> Event {
+        Event::KeyDown {
+            key,
+            modifiers: Modifiers::empty(),
+            is_repeat: false,
+        }
+    }
+
+    fn up(key: KeyCode) -> Event {
+        Event::KeyUp {
+            key,
+            modifiers: Modifiers::empty(),
+        }
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_double_tap_and_hold() {
+        let mut buffer = InputBuffer::new();
+        frame(&mut buffer, 0.0, vec![down(KeyCode::D), up(KeyCode::D)]);
+        assert!(!buffer.double_tap(KeyCode::D, 0.3));
+
+        frame(&mut buffer, 0.2, vec![down(KeyCode::D)]);
+        assert!(buffer.double_tap(KeyCode::D, 0.3));
+
+        frame(&mut buffer, 0.1, vec![]);
+        assert!(!buffer.double_tap(KeyCode::D, 0.3));
+
+        frame(&mut buffer, 0.5, vec![up(KeyCode::D)]);
+        assert!(buffer.held_and_released(KeyCode::D, 0.5));
+        assert!(!buffer.held_and_released(KeyCode::D, 1.0));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_sequence() {
+        let fireball = Sequence::new(0.25)
+            .then(KeyCode::Down)
+            .then_all(vec![KeyCode::Down, KeyCode::Right])
+            .then_all(vec![KeyCode::Right, KeyCode::A]);
+
+        let mut buffer = InputBuffer::new();
+        frame(&mut buffer, 0.0, vec![down(KeyCode::A), up(KeyCode::A)]);
+        frame(&mut buffer, 0.1, vec![down(KeyCode::Down)]);
+        frame(&mut buffer, 0.1, vec![down(KeyCode::Right)]);
+        frame(&mut buffer, 0.1, vec![up(KeyCode::Down)]);
+        assert!(!buffer.sequence(&fireball));
+
+        frame(&mut buffer, 0.1, vec![down(KeyCode::A)]);
+        assert!(buffer.sequence(&fireball));
+
+        frame(&mut buffer, 0.1, vec![]);
+        assert!(!buffer.sequence(&fireball));
+
+        // too slow between the steps
+        frame(&mut buffer, 0.1, vec![up(KeyCode::A), up(KeyCode::Right)]);
+        frame(&mut buffer, 0.1, vec![down(KeyCode::Down)]);
+        frame(&mut buffer, 0.5, vec![down(KeyCode::Right)]);
+        frame(&mut buffer, 0.1, vec![down(KeyCode::A)]);
+        assert!(!buffer.sequence(&fireball));
+    }
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "headless")]
+
+use nae::prelude::*;
+
+#[derive(Clone, Resource)]
+struct Words {
+    words: ResourceCell<Vec<String>>,
+}
+
+impl Words {
+    fn parse(_app: &mut App, data: Vec<u8>) -> Result<Vec<String>, String> {
+        let text = String::from_utf8(data).map_err(|e| e.to_string())?;
+        Ok(text.split_whitespace().map(|w| w.to_string()).collect())
+    }
+}
+
+#[derive(Clone, Resource)]
+#[resource(parse = "parse_number")]
+struct Number(ResourceCell<u32>);
+
+fn parse_number(_app: &mut App, data: Vec<u8>) -> Result<u32, String> {
+    let text = String::from_utf8(data).map_err(|e| e.to_string())?;
+    text.trim()
+        .parse()
+        .map_err(|_| format!("'{}' is not a number", text))
+}
+
+#[test]
+fn test_derive_named_struct() {
+    let mut runner = nae::init().build_headless().unwrap();
+    let app = runner.app();
+
+    let mut words = Words::prepare(app, "words.txt").unwrap();
+    assert!(!words.is_loaded());
+    assert_eq!(words.state(), ResourceState::Pending);
+    assert!(words.data().is_none());
+
+    words
+        .parse_resource(app, b"lorem ipsum dolor".to_vec())
+        .unwrap();
+    assert!(words.is_loaded());
+    assert_eq!(words.state(), ResourceState::Loaded);
+    assert_eq!(*words.data().unwrap(), vec!["lorem", "ipsum", "dolor"]);
+}
+
+#[test]
+fn test_derive_tuple_struct() {
+    let mut runner = nae::init().build_headless().unwrap();
+    let app = runner.app();
+
+    let mut number = Number::prepare(app, "number.txt").unwrap();
+    number.parse_resource(app, b"42".to_vec()).unwrap();
+    assert!(number.is_loaded());
+    assert_eq!(*number.data().unwrap(), 42);
+
+    // the clones share the value
+    let copy = number.clone();
+    *number.data_mut().unwrap() = 7;
+    assert_eq!(*copy.data().unwrap(), 7);
+
+    // the previous value is kept when the data can't be parsed
+    let error = number.parse_resource(app, b"abc".to_vec()).unwrap_err();
+    ResourceParser::set_error(&mut number, error.clone());
+    assert!(!number.is_loaded());
+    assert_eq!(number.state(), ResourceState::Failed(error));
+    assert_eq!(*number.data().unwrap(), 7);
+}
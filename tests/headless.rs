@@ -0,0 +1,60 @@
+#![cfg(feature = "headless")]
+
+use nae::prelude::*;
+
+#[derive(Default)]
+struct State {
+    presses: u32,
+    frames: u32,
+    draw_calls: u32,
+}
+
+#[test]
+fn test_headless_step() {
+    let mut runner = nae::init_with(|_| State::default())
+        .size(320, 240)
+        .update(|app: &mut App, state: &mut State| {
+            if app.keyboard.was_pressed(KeyCode::Space) {
+                state.presses += 1;
+            }
+            state.frames += 1;
+        })
+        .draw(|app: &mut App, state: &mut State| {
+            let draw = app.draw();
+            draw.begin(Color::BLACK);
+            draw.rect(10.0, 10.0, 50.0, 50.0);
+            draw.end();
+
+            // the counters are reset after each frame
+            state.draw_calls = app.gfx().stats().draw_calls;
+        })
+        .build_headless()
+        .unwrap();
+
+    assert!(runner.step());
+    assert_eq!(runner.state().presses, 0);
+
+    runner.push_event(Event::KeyDown {
+        key: KeyCode::Space,
+        modifiers: Modifiers::default(),
+        is_repeat: false,
+    });
+    assert!(runner.step());
+    assert_eq!(runner.state().presses, 1);
+    assert!(runner.app().keyboard.is_down(KeyCode::Space));
+
+    // the key is still down but it's not pressed again
+    assert!(runner.step());
+    assert_eq!(runner.state().presses, 1);
+    assert_eq!(runner.state().frames, 3);
+    assert!(runner.state().draw_calls > 0);
+    assert_eq!(runner.app().gfx().stats().draw_calls, 0);
+    assert_eq!(
+        runner.app().profiler.last().map(|f| f.gfx.draw_calls),
+        Some(runner.state().draw_calls)
+    );
+
+    runner.push_event(Event::Quit);
+    assert!(!runner.step());
+    assert!(!runner.is_running());
+}
@@ -0,0 +1,60 @@
+use nae::prelude::*;
+
+fn frame(buffer: &mut InputBuffer, delta: f32, events: &[Event]) {
+    buffer.next_frame();
+    buffer.tick(delta);
+    events.iter().for_each(|evt| buffer.process(evt));
+}
+
+fn down(key: KeyCode) -> Event {
+    Event::KeyDown {
+        key,
+        modifiers: Modifiers::default(),
+        is_repeat: false,
+    }
+}
+
+fn up(key: KeyCode) -> Event {
+    Event::KeyUp {
+        key,
+        modifiers: Modifiers::default(),
+    }
+}
+
+#[test]
+fn test_input_buffer_public_api() {
+    let dash = Sequence::new(0.3).then(KeyCode::Right).then(KeyCode::Right);
+    let mut buffer = InputBuffer::new();
+
+    frame(
+        &mut buffer,
+        0.0,
+        &[down(KeyCode::Right), up(KeyCode::Right)],
+    );
+    assert!(!buffer.sequence(&dash));
+    assert_eq!(buffer.iter().count(), 2);
+
+    frame(&mut buffer, 0.1, &[down(KeyCode::Right)]);
+    assert!(buffer.sequence(&dash));
+    assert!(buffer.double_tap(KeyCode::Right, 0.3));
+
+    // the match only lasts the frame of its last input
+    frame(&mut buffer, 0.1, &[]);
+    assert!(!buffer.sequence(&dash));
+
+    frame(&mut buffer, 0.8, &[up(KeyCode::Right)]);
+    assert!(buffer.held_and_released(KeyCode::Right, 0.5));
+    assert!((buffer.time() - 1.0).abs() < 0.001);
+
+    // repeated keys are not stored
+    frame(
+        &mut buffer,
+        0.1,
+        &[Event::KeyDown {
+            key: KeyCode::A,
+            modifiers: Modifiers::default(),
+            is_repeat: true,
+        }],
+    );
+    assert_eq!(buffer.iter().count(), 4);
+}
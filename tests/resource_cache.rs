@@ -0,0 +1,97 @@
+#![cfg(feature = "headless")]
+
+use nae::prelude::*;
+use std::time::Duration;
+
+fn wait_default_group(runner: &mut HeadlessRunner<App, ()>) {
+    for _ in 0..500 {
+        runner.step();
+        let complete = runner
+            .app()
+            .resource_group(DEFAULT_GROUP)
+            .map_or(true, |group| group.is_complete());
+
+        if complete {
+            return;
+        }
+
+        std::thread::sleep(Duration::from_millis(2));
+    }
+
+    panic!("The resources didn't finish loading");
+}
+
+#[test]
+fn test_retry_failed_resource() {
+    let mut runner = nae::init().build_headless().unwrap();
+
+    let blob: Blob = runner.app().load_resource("retry/level.txt").unwrap();
+    wait_default_group(&mut runner);
+    assert!(!blob.is_loaded());
+    assert_eq!(
+        runner.app().resource_references::<Blob>("retry/level.txt"),
+        0
+    );
+
+    // the file is available now, the next request loads it again
+    runner
+        .app()
+        .vfs
+        .mount_embedded("retry", vec![("level.txt", &b"level"[..])], 0);
+
+    let blob: Blob = runner.app().load_resource("retry/level.txt").unwrap();
+    wait_default_group(&mut runner);
+    assert!(blob.is_loaded());
+    assert_eq!(*blob.data(), b"level".to_vec());
+}
+
+#[test]
+fn test_cache_keys_keep_the_root_and_parents() {
+    let mut runner = nae::init().build_headless().unwrap();
+    runner
+        .app()
+        .vfs
+        .mount_embedded("x", vec![("a.txt", &b"a"[..])], 0);
+
+    let _a: Blob = runner.app().load_resource("x/a.txt").unwrap();
+    let _b: Blob = runner.app().load_resource("x/../x/a.txt").unwrap();
+    let _c: Blob = runner.app().load_resource("/x/a.txt").unwrap();
+    let _d: Blob = runner.app().load_resource("../x/a.txt").unwrap();
+
+    let app = runner.app();
+    assert_eq!(app.resource_references::<Blob>("x/a.txt"), 2);
+    assert_eq!(app.resource_references::<Blob>("/x/a.txt"), 1);
+    assert_eq!(app.resource_references::<Blob>("../x/a.txt"), 1);
+}
+
+#[test]
+fn test_group_ready_after_unload() {
+    let mut runner = nae::init_with(|_| 0)
+        .event(|_: &mut App, ready: &mut u32, evt: Event| {
+            if let Event::ResourceGroupReady { group } = evt {
+                assert_eq!(group, "level");
+                *ready += 1;
+            }
+        })
+        .build_headless()
+        .unwrap();
+
+    runner
+        .app()
+        .vfs
+        .mount_embedded("x", vec![("a.txt", &b"a"[..])], 0);
+
+    let _a: Blob = runner.app().load_resource_in("level", "x/a.txt").unwrap();
+    let _b: Blob = runner.app().load_resource_in("level", "x/a.txt").unwrap();
+    assert!(!runner.app().unload_resource::<Blob>("x/a.txt"));
+    assert_eq!(runner.app().resource_group("level").unwrap().total, 1);
+
+    // the group is complete without loading the resource
+    assert!(runner.app().unload_resource::<Blob>("x/a.txt"));
+    assert!(runner.step());
+    assert_eq!(
+        runner.app().resource_group("level").unwrap().progress(),
+        1.0
+    );
+    assert_eq!(*runner.state(), 1);
+}